@@ -1,43 +1,64 @@
 use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields, GenericParam, Index};
+use syn::{
+    parse_macro_input, parse_quote, Data, DataEnum, DeriveInput, Fields, Generics, Ident, Index,
+    Member, Variant,
+};
 
-#[proc_macro_derive(Semilattice)]
+/// Structs are ordered and joined field by field, as a product. Enums are an
+/// ordered sum: a later variant is above every value of an earlier one, and
+/// values of the same variant are ordered and joined by their fields. A unit
+/// variant marked `#[semilattice(bottom)]` or `#[semilattice(top)]` is below or
+/// above all others instead. A `#[default]` variant must be the bottom one, or
+/// the first if none is marked.
+#[proc_macro_derive(Semilattice, attributes(semilattice))]
 pub fn derive_semilattice(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
-
-    let semilattice_impl = {
-        let mut generics = input.generics.clone();
+    let checked = supported(&input.data, "Semilattice", true).and_then(|()| match input.data {
+        Data::Enum(ref data) => check_markers(data).and_then(|()| default_is_bottom(data)),
+        _ => Ok(()),
+    });
+    if let Err(error) = checked {
+        return error.to_compile_error().into();
+    }
 
-        for param in &mut generics.params {
-            if let GenericParam::Type(ref mut type_param) = *param {
-                type_param.bounds.push(parse_quote!(semilog::Semilattice));
-            }
-        }
+    let name = &input.ident;
+    let generics = bounded(&input, quote!(semilog::Semilattice));
 
+    let semilattice_impl = {
         let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
         let join = semilattice_join(&input.data);
+        let delta = semilattice_delta(&input.data);
+        let join_assign_changed = semilattice_join_assign_changed(&input.data);
+        let (leq, is_bottom) = semilattice_leq(&input.data);
 
         quote!(
             impl #impl_generics semilog::Semilattice for #name #ty_generics #where_clause {
                 fn join(self, other: Self) -> Self {
                     #join
                 }
+
+                fn join_assign_changed(&mut self, other: Self) -> bool {
+                    #join_assign_changed
+                }
+
+                fn leq(&self, other: &Self) -> bool {
+                    #leq
+                }
+
+                fn is_bottom(&self) -> bool {
+                    #is_bottom
+                }
+
+                fn delta(self, since: &Self) -> Self {
+                    #delta
+                }
             }
         )
     };
 
     let partial_cmp = {
-        let mut generics = input.generics;
-
-        for param in &mut generics.params {
-            if let GenericParam::Type(ref mut type_param) = *param {
-                type_param.bounds.push(parse_quote!(semilog::Semilattice));
-            }
-        }
-
         let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
         let partial_cmp = partial_ord_cmp(&input.data);
 
@@ -58,6 +79,153 @@ pub fn derive_semilattice(input: proc_macro::TokenStream) -> proc_macro::TokenSt
     .into()
 }
 
+#[proc_macro_derive(Decompose)]
+pub fn derive_decompose(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    if let Err(error) = supported(&input.data, "Decompose", false) {
+        return error.to_compile_error().into();
+    }
+
+    let name = &input.ident;
+    let generics = bounded(&input, quote!(semilog::Decompose));
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let decompose = decompose_fields(&input.data);
+
+    quote!(
+        impl #impl_generics semilog::Decompose for #name #ty_generics #where_clause {
+            fn decompose(self, mut func: impl FnMut(Self)) {
+                #decompose
+            }
+        }
+    )
+    .into()
+}
+
+/// Structs are joined from a borrowed value field by field, so only the
+/// fields which change clone anything. Enums clone a greater variant as a
+/// whole.
+#[proc_macro_derive(JoinRef)]
+pub fn derive_join_ref(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    if let Err(error) = supported(&input.data, "JoinRef", true) {
+        return error.to_compile_error().into();
+    }
+
+    let name = &input.ident;
+    let mut generics = bounded(&input, quote!(semilog::JoinRef));
+
+    // a derived `Clone` bounds the type parameters, rather than the fields.
+    if input.generics.type_params().next().is_some() {
+        let (_, ty_generics, _) = input.generics.split_for_impl();
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#name #ty_generics: core::clone::Clone));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let join_assign_ref = match input.data {
+        Data::Struct(_) => {
+            let join_assign_ref = join_ref_fields(&input.data);
+            quote! {
+                fn join_assign_ref(&mut self, other: &Self) {
+                    #join_assign_ref
+                }
+            }
+        }
+        _ => quote!(),
+    };
+
+    quote!(
+        impl #impl_generics semilog::JoinRef for #name #ty_generics #where_clause {
+            #join_assign_ref
+        }
+    )
+    .into()
+}
+
+#[proc_macro_derive(BoundedLattice, attributes(semilattice))]
+pub fn derive_bounded_lattice(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let checked = supported(&input.data, "BoundedLattice", true).and_then(|()| match input.data {
+        Data::Enum(ref data) if data.variants.is_empty() => Err(syn::Error::new_spanned(
+            &input.ident,
+            "`BoundedLattice` cannot be derived for enums without variants",
+        )),
+        Data::Enum(ref data) => check_markers(data),
+        _ => Ok(()),
+    });
+    if let Err(error) = checked {
+        return error.to_compile_error().into();
+    }
+
+    let name = &input.ident;
+    let generics = bounded(&input, quote!(semilog::BoundedLattice));
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (top, is_top) = bounded_lattice_top(&input.data);
+
+    quote!(
+        impl #impl_generics semilog::BoundedLattice for #name #ty_generics #where_clause {
+            fn top() -> Self {
+                #top
+            }
+
+            fn is_top(&self) -> bool {
+                #is_top
+            }
+        }
+    )
+    .into()
+}
+
+#[proc_macro_derive(Lattice)]
+pub fn derive_lattice(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    if let Err(error) = supported(&input.data, "Lattice", false) {
+        return error.to_compile_error().into();
+    }
+
+    let name = &input.ident;
+    let generics = bounded(&input, quote!(semilog::Lattice));
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let meet = lattice_meet(&input.data);
+
+    quote!(
+        impl #impl_generics semilog::Lattice for #name #ty_generics #where_clause {
+            fn meet(self, other: Self) -> Self {
+                #meet
+            }
+        }
+    )
+    .into()
+}
+
+#[proc_macro_derive(Finite)]
+pub fn derive_finite(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    if let Err(error) = supported(&input.data, "Finite", false) {
+        return error.to_compile_error().into();
+    }
+
+    let name = &input.ident;
+    let generics = bounded(&input, quote!(semilog::Finite));
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let for_each_element = finite_for_each_element(&input.data);
+
+    quote!(
+        impl #impl_generics semilog::Finite for #name #ty_generics #where_clause {
+            fn for_each_element(mut func: impl FnMut(Self)) {
+                #for_each_element
+            }
+        }
+    )
+    .into()
+}
+
 fn semilattice_join(data: &Data) -> TokenStream {
     match *data {
         Data::Struct(ref data) => match data.fields {
@@ -89,7 +257,561 @@ fn semilattice_join(data: &Data) -> TokenStream {
                 quote!(Self)
             }
         },
-        Data::Enum(_) | Data::Union(_) => unimplemented!(),
+        Data::Enum(ref data) => {
+            let arms = variant_pairs(data, |a, b| {
+                quote! { semilog::Semilattice::join(#a, #b) }
+            });
+            enum_match(
+                data,
+                quote!((self, other)),
+                arms,
+                quote! {
+                    (a, b) => if rank(&a) < rank(&b) { b } else { a },
+                },
+            )
+        }
+        Data::Union(_) => unreachable!(),
+    }
+}
+
+fn semilattice_join_assign_changed(data: &Data) -> TokenStream {
+    match *data {
+        Data::Struct(ref data) => {
+            // not short-circuiting, as every field must be joined.
+            let fields = data.fields.iter().enumerate().map(|(i, f)| {
+                let member = match f.ident {
+                    Some(ref ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                };
+                quote_spanned! { f.span() =>
+                    | semilog::Semilattice::join_assign_changed(&mut self.#member, other.#member)
+                }
+            });
+            quote! {
+                false #(#fields)*
+            }
+        }
+        Data::Enum(ref data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let (a, b, pattern) = variant_patterns(variant);
+                quote! {
+                    #pattern => false #(| semilog::Semilattice::join_assign_changed(#a, #b))*,
+                }
+            });
+            enum_match(
+                data,
+                quote!((self, other)),
+                quote!(#(#arms)*),
+                quote! {
+                    (a, b) => if rank(&*a) < rank(&b) {
+                        *a = b;
+                        true
+                    } else {
+                        false
+                    },
+                },
+            )
+        }
+        Data::Union(_) => unreachable!(),
+    }
+}
+
+fn join_ref_fields(data: &Data) -> TokenStream {
+    match *data {
+        Data::Struct(ref data) => {
+            let fields = data.fields.iter().enumerate().map(|(i, f)| {
+                let member = match f.ident {
+                    Some(ref ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                };
+                quote_spanned! { f.span() =>
+                    semilog::JoinRef::join_assign_ref(&mut self.#member, &other.#member);
+                }
+            });
+            quote! {
+                #(#fields)*
+            }
+        }
+        Data::Enum(_) | Data::Union(_) => unreachable!(),
+    }
+}
+
+fn semilattice_leq(data: &Data) -> (TokenStream, TokenStream) {
+    match *data {
+        Data::Struct(ref data) => {
+            // the product order, stopping at the first field which is not.
+            let members: Vec<_> = data
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| match f.ident {
+                    Some(ref ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                })
+                .collect();
+            (
+                quote! {
+                    true #(&& semilog::Semilattice::leq(&self.#members, &other.#members))*
+                },
+                quote! {
+                    true #(&& semilog::Semilattice::is_bottom(&self.#members))*
+                },
+            )
+        }
+        Data::Enum(ref data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let (a, b, pattern) = variant_patterns(variant);
+                quote! {
+                    #pattern => true #(&& semilog::Semilattice::leq(#a, #b))*,
+                }
+            });
+            let leq = enum_match(
+                data,
+                quote!((self, other)),
+                quote!(#(#arms)*),
+                quote! {
+                    (a, b) => rank(a) < rank(b),
+                },
+            );
+            let is_bottom = match marked_variant(data, "bottom") {
+                Some(variant) => {
+                    let ident = &variant.ident;
+                    quote!(core::matches!(self, Self::#ident { .. }))
+                }
+                None => quote! {
+                    semilog::Semilattice::leq(self, &core::default::Default::default())
+                },
+            };
+            (leq, is_bottom)
+        }
+        Data::Union(_) => unreachable!(),
+    }
+}
+
+fn semilattice_delta(data: &Data) -> TokenStream {
+    match *data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => {
+                let fields = fields.named.iter().map(|f| {
+                    let name = &f.ident;
+                    quote_spanned! { f.span() =>
+                        #name: semilog::Semilattice::delta(self.#name, &since.#name),
+                    }
+                });
+                quote! {
+                    Self {
+                        #(#fields)*
+                    }
+                }
+            }
+            Fields::Unnamed(ref fields) => {
+                let fields = fields.unnamed.iter().enumerate().map(|(i, f)| {
+                    let index = Index::from(i);
+                    quote_spanned! { f.span() =>
+                        semilog::Semilattice::delta(self.#index, &since.#index),
+                    }
+                });
+                quote! {
+                    Self(#(#fields)*)
+                }
+            }
+            Fields::Unit => {
+                quote!(Self)
+            }
+        },
+        Data::Enum(ref data) => {
+            // a value of the same variant keeps the deltas of its fields, and
+            // one of a later variant is kept whole.
+            let arms = variant_pairs(data, |a, b| {
+                quote! { semilog::Semilattice::delta(#a, #b) }
+            });
+            let delta = enum_match(data, quote!((self, since)), arms, quote!((a, _) => a,));
+            quote! {
+                if semilog::Semilattice::leq(&self, since) {
+                    return core::default::Default::default();
+                }
+                #delta
+            }
+        }
+        Data::Union(_) => unreachable!(),
+    }
+}
+
+fn finite_for_each_element(data: &Data) -> TokenStream {
+    match *data {
+        Data::Struct(ref data) => {
+            let members: Vec<_> = data
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| match f.ident {
+                    Some(ref ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                })
+                .collect();
+            let vars: Vec<_> = (0..members.len())
+                .map(|i| quote::format_ident!("field_{}", i))
+                .collect();
+
+            // the cartesian product of all fields, as nested loops.
+            let mut product = quote! {
+                func(Self {
+                    #(#members: core::clone::Clone::clone(&#vars),)*
+                })
+            };
+            for (f, var) in data.fields.iter().zip(&vars).rev() {
+                let ty = &f.ty;
+                product = quote_spanned! { f.span() =>
+                    <#ty as semilog::Finite>::for_each_element(|#var| { #product })
+                };
+            }
+            product
+        }
+        Data::Enum(_) | Data::Union(_) => unreachable!(),
+    }
+}
+
+fn lattice_meet(data: &Data) -> TokenStream {
+    match *data {
+        Data::Struct(ref data) => {
+            let fields = data.fields.iter().enumerate().map(|(i, f)| {
+                let member = match f.ident {
+                    Some(ref ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                };
+                quote_spanned! { f.span() =>
+                    #member: semilog::Lattice::meet(self.#member, other.#member),
+                }
+            });
+            quote! {
+                Self {
+                    #(#fields)*
+                }
+            }
+        }
+        Data::Enum(_) | Data::Union(_) => unreachable!(),
+    }
+}
+
+fn bounded_lattice_top(data: &Data) -> (TokenStream, TokenStream) {
+    match *data {
+        Data::Struct(ref data) => {
+            let members = data.fields.iter().enumerate().map(|(i, f)| match f.ident {
+                Some(ref ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(i)),
+            });
+            let tops = data.fields.iter().zip(members.clone()).map(|(f, member)| {
+                quote_spanned! { f.span() =>
+                    #member: semilog::BoundedLattice::top(),
+                }
+            });
+            let is_tops = data.fields.iter().zip(members).map(|(f, member)| {
+                quote_spanned! { f.span() =>
+                    && semilog::BoundedLattice::is_top(&self.#member)
+                }
+            });
+            (
+                quote! {
+                    Self {
+                        #(#tops)*
+                    }
+                },
+                quote! {
+                    true #(#is_tops)*
+                },
+            )
+        }
+        Data::Enum(ref data) => {
+            // the top of the greatest variant.
+            let variant = marked_variant(data, "top")
+                .or_else(|| {
+                    data.variants
+                        .iter()
+                        .rev()
+                        .find(|v| variant_marker(v).is_none())
+                })
+                .or_else(|| data.variants.last())
+                .expect("BUG!");
+            let ident = &variant.ident;
+            let (a, _, _) = variant_patterns(variant);
+            let members = members(&variant.fields);
+            let is_top = if data.variants.len() > 1 {
+                quote!(_ => false,)
+            } else {
+                quote!()
+            };
+            (
+                quote! {
+                    Self::#ident {
+                        #(#members: semilog::BoundedLattice::top(),)*
+                    }
+                },
+                quote! {
+                    match self {
+                        Self::#ident { #(#members: #a),* } => {
+                            true #(&& semilog::BoundedLattice::is_top(#a))*
+                        }
+                        #is_top
+                    }
+                },
+            )
+        }
+        Data::Union(_) => unreachable!(),
+    }
+}
+
+// Only structs, and enums if `enums` is set, can derive `name`.
+fn supported(data: &Data, name: &str, enums: bool) -> syn::Result<()> {
+    match *data {
+        Data::Struct(_) => Ok(()),
+        Data::Enum(_) if enums => Ok(()),
+        Data::Enum(ref data) => Err(syn::Error::new_spanned(
+            data.enum_token,
+            format!("`{}` cannot be derived for enums", name),
+        )),
+        Data::Union(ref data) => Err(syn::Error::new_spanned(
+            data.union_token,
+            format!("`{}` cannot be derived for unions", name),
+        )),
+    }
+}
+
+// The generics of `input`, with the type of each field bounded by `bound`.
+// Bounding the fields rather than the type parameters allows a parameter
+// which does not implement the trait itself, such as the data of a `Flat`.
+fn bounded(input: &DeriveInput, bound: TokenStream) -> Generics {
+    let mut generics = input.generics.clone();
+
+    if generics.type_params().next().is_some() {
+        let fields: Vec<_> = match input.data {
+            Data::Struct(ref data) => data.fields.iter().collect(),
+            Data::Enum(ref data) => data.variants.iter().flat_map(|v| &v.fields).collect(),
+            Data::Union(ref data) => data.fields.named.iter().collect(),
+        };
+
+        let where_clause = generics.make_where_clause();
+        for field in fields {
+            let ty = &field.ty;
+            where_clause.predicates.push(parse_quote!(#ty: #bound));
+        }
+    }
+
+    generics
+}
+
+// The `#[default]` variant of an enum must be its bottom: the variant marked
+// `#[semilattice(bottom)]`, or else the first one.
+fn default_is_bottom(data: &DataEnum) -> syn::Result<()> {
+    let bottom = marked_variant(data, "bottom").or_else(|| data.variants.first());
+    let bottom = bottom.map(|variant| &variant.ident);
+
+    for variant in &data.variants {
+        let default = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path.is_ident("default"));
+
+        if let Some(attr) = default {
+            if Some(&variant.ident) != bottom {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "The `#[default]` variant must be the bottom, which is the \
+                     `#[semilattice(bottom)]` variant or else the first one",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn members(fields: &Fields) -> Vec<Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match f.ident {
+            Some(ref ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        })
+        .collect()
+}
+
+// Whether a variant is marked as the bottom or the top of an enum. The
+// markers are checked by `check_markers` before any code is generated.
+fn variant_marker(variant: &Variant) -> Option<String> {
+    let attr = variant
+        .attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("semilattice"))?;
+    attr.parse_args::<Ident>()
+        .map(|ident| ident.to_string())
+        .ok()
+}
+
+fn marked_variant<'a>(data: &'a DataEnum, marker: &str) -> Option<&'a Variant> {
+    data.variants
+        .iter()
+        .find(|v| variant_marker(v).as_deref() == Some(marker))
+}
+
+// Each marked variant has a single `#[semilattice(top)]` or
+// `#[semilattice(bottom)]` attribute and no fields, and an enum has at most
+// one variant with each marker.
+fn check_markers(data: &DataEnum) -> syn::Result<()> {
+    let mut marked: Vec<String> = Vec::new();
+
+    for variant in &data.variants {
+        let mut attrs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("semilattice"));
+        let attr = match attrs.next() {
+            Some(attr) => attr,
+            None => continue,
+        };
+
+        if let Some(duplicate) = attrs.next() {
+            return Err(syn::Error::new_spanned(
+                duplicate,
+                "A variant has at most one `#[semilattice(..)]` attribute",
+            ));
+        }
+
+        let marker = attr
+            .parse_args::<Ident>()
+            .ok()
+            .map(|ident| ident.to_string())
+            .filter(|marker| marker == "top" || marker == "bottom")
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    attr,
+                    "Expected `#[semilattice(top)]` or `#[semilattice(bottom)]`",
+                )
+            })?;
+
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                format!("The {} of an enum must be a unit variant", marker),
+            ));
+        }
+
+        if marked.contains(&marker) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!("An enum has at most one {} variant", marker),
+            ));
+        }
+        marked.push(marker);
+    }
+
+    Ok(())
+}
+
+// The fields of a variant bound as `a_0, a_1, ..` and `b_0, b_1, ..`, and a
+// pattern matching a pair of values of that variant.
+fn variant_patterns(variant: &Variant) -> (Vec<Ident>, Vec<Ident>, TokenStream) {
+    let ident = &variant.ident;
+    let members = members(&variant.fields);
+    let a: Vec<_> = (0..members.len())
+        .map(|i| quote::format_ident!("a_{}", i))
+        .collect();
+    let b: Vec<_> = (0..members.len())
+        .map(|i| quote::format_ident!("b_{}", i))
+        .collect();
+    let pattern = quote! {
+        (Self::#ident { #(#members: #a),* }, Self::#ident { #(#members: #b),* })
+    };
+    (a, b, pattern)
+}
+
+// The match arms building a value of the same variant from each pair of
+// fields.
+fn variant_pairs(data: &DataEnum, field: impl Fn(&Ident, &Ident) -> TokenStream) -> TokenStream {
+    let arms = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let members = members(&variant.fields);
+        let (a, b, pattern) = variant_patterns(variant);
+        let fields = a.iter().zip(&b).map(|(a, b)| field(a, b));
+        quote! {
+            #pattern => Self::#ident { #(#members: #fields),* },
+        }
+    });
+    quote!(#(#arms)*)
+}
+
+// Match a pair of values by variant. Values of different variants fall back
+// to comparing the rank of their variants, in the order of the sum.
+fn enum_match(
+    data: &DataEnum,
+    pair: TokenStream,
+    arms: TokenStream,
+    fallback: TokenStream,
+) -> TokenStream {
+    if data.variants.len() < 2 {
+        return quote! {
+            match #pair {
+                #arms
+            }
+        };
+    }
+
+    let bottom = marked_variant(data, "bottom").map(|v| &v.ident);
+    let top = marked_variant(data, "top").map(|v| &v.ident);
+    let mut next = bottom.is_some() as usize;
+    let ranks = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let rank = if Some(ident) == bottom {
+            0
+        } else if Some(ident) == top {
+            data.variants.len() - 1
+        } else {
+            next += 1;
+            next - 1
+        };
+        quote! {
+            Self::#ident { .. } => #rank,
+        }
+    });
+
+    quote! {
+        let rank = |value: &Self| -> usize {
+            match *value {
+                #(#ranks)*
+            }
+        };
+
+        match #pair {
+            #arms
+            #fallback
+        }
+    }
+}
+
+fn decompose_fields(data: &Data) -> TokenStream {
+    match *data {
+        Data::Struct(ref data) => {
+            // each part of each field, with every other field at bottom.
+            let rest = (data.fields.len() > 1).then(|| quote!(..core::default::Default::default()));
+            let fields = data.fields.iter().enumerate().map(|(i, f)| {
+                let member = match f.ident {
+                    Some(ref ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i)),
+                };
+                quote_spanned! { f.span() =>
+                    semilog::Decompose::decompose(self.#member, |part| {
+                        func(Self {
+                            #member: part,
+                            #rest
+                        })
+                    });
+                }
+            });
+            quote! {
+                #(#fields)*
+            }
+        }
+        Data::Enum(_) | Data::Union(_) => unreachable!(),
     }
 }
 
@@ -122,6 +844,24 @@ fn partial_ord_cmp(data: &Data) -> TokenStream {
                 quote!(core::option::Option::Some(core::cmp::Ordering::Equal))
             }
         },
-        Data::Enum(_) | Data::Union(_) => unimplemented!(),
+        Data::Enum(ref data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let (a, b, pattern) = variant_patterns(variant);
+                quote! {
+                    #pattern => semilog::partial_ord_helper([
+                        #(PartialOrd::partial_cmp(#a, #b),)*
+                    ]),
+                }
+            });
+            enum_match(
+                data,
+                quote!((self, other)),
+                quote!(#(#arms)*),
+                quote! {
+                    (a, b) => PartialOrd::partial_cmp(&rank(a), &rank(b)),
+                },
+            )
+        }
+        Data::Union(_) => unreachable!(),
     }
 }
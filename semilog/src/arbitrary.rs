@@ -0,0 +1,701 @@
+use core::fmt;
+
+use proptest::{
+    arbitrary::{any, any_with, Arbitrary},
+    prop_assert, prop_assert_eq, prop_oneof,
+    strategy::{BoxedStrategy, Just, Strategy},
+    test_runner::{Config, TestCaseError, TestError, TestRunner},
+};
+
+use crate::{
+    ArrayMapLattice, ArraySetLattice, ArrayVecLattice, BitSetLattice, BoundedLattice,
+    ConflictMetadata, Flat, GuardedPair, Hlc, Interval, Lww, Max, Maximum, Min, Minimum, Pair,
+    Redactable, Semilattice, Toggle, TotalFloat, Words,
+};
+
+#[cfg(all(test, feature = "alloc"))]
+use crate::JoinRef;
+
+#[cfg(feature = "alloc")]
+use alloc::vec;
+
+#[cfg(feature = "alloc")]
+use crate::{
+    fold, Dot, GCounter, MapLattice, MvRegister, OrMap, OrSet, PNCounter, Position, Sequence,
+    SetLattice, TombstoneMap, TwoPhaseSet, VecLattice, VersionVector,
+};
+
+/// Check the semilattice laws of `S` against randomly generated values. On
+/// failure, the returned error holds a shrunk counterexample.
+///
+/// ```lean
+/// ∀ a b c ∈ S,
+///   (a + b) + c = a + (b + c)
+///   ∧ a + b = b + a
+///   ∧ a + a = a
+///   ∧ ⊥ + a = a
+///   ∧ (a ≤ b ↔ a + b = b)
+///   ∧ (a ≤ b ∧ b ≤ a → a = b)
+///   ∧ (a ≤ b ∧ b ≤ c → a ≤ c)
+/// ```
+pub fn check_laws<S>() -> Result<(), TestError<(S, S, S)>>
+where
+    S: Semilattice + Arbitrary + Clone + fmt::Debug,
+{
+    let config = Config {
+        failure_persistence: None,
+        ..Config::default()
+    };
+
+    TestRunner::new(config).run(&any::<(S, S, S)>(), |(a, b, c)| laws(a, b, c))
+}
+
+fn laws<S>(a: S, b: S, c: S) -> Result<(), TestCaseError>
+where
+    S: Semilattice + Clone + fmt::Debug,
+{
+    let bottom = S::default();
+    let ab = a.clone().join(b.clone());
+
+    // associative
+    prop_assert_eq!(
+        ab.clone().join(c.clone()),
+        a.clone().join(b.clone().join(c.clone()))
+    );
+    // commutative
+    prop_assert_eq!(&ab, &b.clone().join(a.clone()));
+    // idempotent
+    prop_assert_eq!(&a, &a.clone().join(a.clone()));
+
+    // bottom is the least element and the identity of the join
+    prop_assert!(bottom <= a, "{:?} <= {:?}", bottom, a);
+    prop_assert_eq!(&a, &bottom.join(a.clone()));
+
+    // the partial order is reflexive, and consistent with equality
+    prop_assert_eq!(a.partial_cmp(&a), Some(core::cmp::Ordering::Equal));
+    prop_assert_eq!(a.partial_cmp(&b), b.partial_cmp(&a).map(|o| o.reverse()));
+    prop_assert_eq!(
+        a == b,
+        a.partial_cmp(&b) == Some(core::cmp::Ordering::Equal)
+    );
+
+    // the partial order is consistent with the least upper bound
+    prop_assert_eq!(a <= b, ab == b, "{:?} <= {:?}; {:?}", a, b, ab);
+    prop_assert!(a <= ab && b <= ab, "{:?} is not an upper bound", ab);
+
+    // antisymmetric
+    if a <= b && b <= a {
+        prop_assert_eq!(&a, &b);
+    }
+
+    // transitive, both for the samples and for a chain built from them
+    if a <= b && b <= c {
+        prop_assert!(a <= c, "{:?} <= {:?}", a, c);
+    }
+    let abc = ab.clone().join(c.clone());
+    prop_assert!(a <= ab && ab <= abc && a <= abc, "{:?} <= {:?}", a, abc);
+
+    // the shortcuts agree with the partial order
+    prop_assert_eq!(a.leq(&b), a <= b);
+    prop_assert_eq!(a.is_bottom(), a <= S::default());
+
+    // deltas and change reports agree with the join
+    prop_assert_eq!(&ab, &b.clone().join(a.clone().delta(&b)));
+    let mut changed = a.clone();
+    prop_assert_eq!(changed.join_assign_changed(b), ab != a);
+    prop_assert_eq!(&changed, &ab);
+
+    Ok(())
+}
+
+impl<T> Arbitrary for Max<T>
+where
+    T: Arbitrary + 'static,
+{
+    type Parameters = T::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<T>(args).prop_map(Max).boxed()
+    }
+}
+
+impl<T> Arbitrary for Min<T>
+where
+    T: Arbitrary + 'static,
+{
+    type Parameters = T::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<T>(args).prop_map(Min).boxed()
+    }
+}
+
+impl<T> Arbitrary for Interval<T>
+where
+    T: Arbitrary + num_traits::bounds::Bounded + Ord + Clone + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        // crossed bounds all collapse to the top element, so generate it
+        // explicitly rather than for half of all pairs.
+        prop_oneof![
+            9 => any::<(T, T)>().prop_map(|(a, b)| {
+                if a <= b {
+                    Interval::from((a, b))
+                } else {
+                    Interval::from((b, a))
+                }
+            }),
+            1 => Just(Interval::top()),
+        ]
+        .boxed()
+    }
+}
+
+impl<A, B> Arbitrary for Pair<A, B>
+where
+    A: Arbitrary + 'static,
+    B: Arbitrary + 'static,
+{
+    type Parameters = (A::Parameters, B::Parameters);
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<(A, B)>(args)
+            .prop_map(|(a, b)| Pair(a, b))
+            .boxed()
+    }
+}
+
+impl<G, V> Arbitrary for GuardedPair<G, V>
+where
+    G: Arbitrary + 'static,
+    V: Arbitrary + 'static,
+{
+    type Parameters = (G::Parameters, V::Parameters);
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<(G, V)>(args)
+            .prop_map(|(guard, value)| GuardedPair { guard, value })
+            .boxed()
+    }
+}
+
+impl<T, M> Arbitrary for Flat<T, M>
+where
+    T: Arbitrary + PartialEq + Clone + 'static,
+    M: Arbitrary + ConflictMetadata<T> + Clone + 'static,
+{
+    type Parameters = (T::Parameters, M::Parameters);
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((args, metadata): Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            1 => Just(Flat::Bottom),
+            8 => any_with::<T>(args).prop_map(Flat::Known),
+            1 => any_with::<M>(metadata).prop_map(Flat::conflict),
+        ]
+        .boxed()
+    }
+}
+
+impl<T, M> Arbitrary for Redactable<T, M>
+where
+    T: Arbitrary + PartialEq + Clone + 'static,
+    M: Arbitrary + ConflictMetadata<T> + Clone + 'static,
+{
+    type Parameters = (T::Parameters, M::Parameters);
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<Flat<T, M>>(args)
+            .prop_map(|inner| Redactable { inner })
+            .boxed()
+    }
+}
+
+// The fixed capacity collections are generated with up to one more item than
+// fits, so they sometimes saturate.
+impl<K, V, const N: usize> Arbitrary for ArrayMapLattice<K, V, N>
+where
+    K: Arbitrary + Ord + Clone + 'static,
+    V: Arbitrary + Semilattice + Clone + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::collection::vec(any::<(K, V)>(), 0..=N + 1)
+            .prop_map(ArrayMapLattice::from_iter)
+            .boxed()
+    }
+}
+
+impl<T, const N: usize> Arbitrary for ArraySetLattice<T, N>
+where
+    T: Arbitrary + Ord + Clone + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::collection::vec(any::<T>(), 0..=N + 1)
+            .prop_map(ArraySetLattice::from_iter)
+            .boxed()
+    }
+}
+
+impl<T, const N: usize> Arbitrary for ArrayVecLattice<T, N>
+where
+    T: Arbitrary + Semilattice + Clone + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::collection::vec(any::<T>(), 0..=N + 1)
+            .prop_map(|items| {
+                let mut vec = ArrayVecLattice::default();
+                // an error leaves the vector saturated.
+                let _ = items.into_iter().try_for_each(|item| vec.push(item));
+                vec
+            })
+            .boxed()
+    }
+}
+
+impl<W> Arbitrary for BitSetLattice<W>
+where
+    W: Words + fmt::Debug + Clone + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        // bits which do not fit in a fixed size set are skipped.
+        proptest::collection::vec(0..128usize, 0..8)
+            .prop_map(|bits| {
+                bits.into_iter()
+                    .filter(|bit| W::zeroed().grow(bit / 64 + 1))
+                    .collect()
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Hlc {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        // a small range of wall-clock times, so that ties are common.
+        (0..4u64, any::<u32>())
+            .prop_map(|(wall, logical)| Hlc { wall, logical })
+            .boxed()
+    }
+}
+
+impl<A, T> Arbitrary for Lww<A, T>
+where
+    A: Arbitrary + 'static,
+    T: Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<Option<(Hlc, A, T)>>()
+            .prop_map(|inner| Lww { inner })
+            .boxed()
+    }
+}
+
+impl<T> Arbitrary for Maximum<T>
+where
+    T: Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<Option<T>>()
+            .prop_map(|inner| Maximum { inner })
+            .boxed()
+    }
+}
+
+impl<T> Arbitrary for Minimum<T>
+where
+    T: Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<Option<T>>()
+            .prop_map(|inner| Minimum { inner })
+            .boxed()
+    }
+}
+
+impl<F> Arbitrary for TotalFloat<F>
+where
+    F: Arbitrary + 'static,
+{
+    type Parameters = F::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<F>(args).prop_map(TotalFloat).boxed()
+    }
+}
+
+impl<S> Arbitrary for Toggle<S>
+where
+    S: 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (0..16u64).prop_map(Toggle::new).boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V> Arbitrary for MapLattice<K, V>
+where
+    K: Arbitrary + Ord + 'static,
+    V: Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::collection::btree_map(any::<K>(), any::<V>(), 0..8)
+            .prop_map(|map| MapLattice {
+                inner: map.into_iter().collect(),
+            })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Arbitrary for SetLattice<T>
+where
+    T: Arbitrary + Ord + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::collection::btree_set(any::<T>(), 0..8)
+            .prop_map(SetLattice::from_iter)
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Arbitrary for VecLattice<T>
+where
+    T: Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::collection::vec(any::<T>(), 0..8)
+            .prop_map(|inner| VecLattice { inner })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A> Arbitrary for GCounter<A>
+where
+    A: Arbitrary + Ord + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<MapLattice<A, Max<u64>>>()
+            .prop_map(|inner| GCounter { inner })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A> Arbitrary for PNCounter<A>
+where
+    A: Arbitrary + Ord + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<(GCounter<A>, GCounter<A>)>()
+            .prop_map(|(increments, decrements)| PNCounter {
+                increments,
+                decrements,
+            })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A> Arbitrary for VersionVector<A>
+where
+    A: Arbitrary + Ord + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<MapLattice<A, Max<u64>>>()
+            .prop_map(|inner| VersionVector { inner })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, T> Arbitrary for MvRegister<A, T>
+where
+    A: Arbitrary + Ord + 'static,
+    T: Arbitrary + Ord + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::collection::vec(any::<(VersionVector<A>, T)>(), 0..4)
+            .prop_map(|writes| {
+                fold(
+                    writes
+                        .into_iter()
+                        .map(|write| MvRegister { inner: vec![write] }),
+                )
+            })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A> Arbitrary for Dot<A>
+where
+    A: Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<A>(), 1..4u64)
+            .prop_map(|(actor, counter)| Dot { actor, counter })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, K, V> Arbitrary for OrMap<A, K, V>
+where
+    A: Arbitrary + Ord + Clone + 'static,
+    K: Arbitrary + Ord + 'static,
+    V: Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        // the context must contain every dot of the entries.
+        (
+            any::<MapLattice<K, MapLattice<Dot<A>, V>>>(),
+            any::<VersionVector<A>>(),
+        )
+            .prop_map(|(mut entries, context)| {
+                entries.inner.retain(|(_, dots)| !dots.is_empty());
+                let dots = entries
+                    .iter()
+                    .flat_map(|(_, dots)| dots.iter().map(|(dot, _)| dot.clone()));
+
+                OrMap {
+                    context: context.join(dots.collect()),
+                    entries,
+                }
+            })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, T> Arbitrary for OrSet<A, T>
+where
+    A: Arbitrary + Ord + Clone + 'static,
+    T: Arbitrary + Ord + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        // the context must contain every dot of the entries.
+        (
+            any::<MapLattice<T, SetLattice<Dot<A>>>>(),
+            any::<VersionVector<A>>(),
+        )
+            .prop_map(|(entries, context)| {
+                let dots = entries.iter().flat_map(|(_, dots)| dots.iter().cloned());
+
+                OrSet {
+                    context: context.join(dots.collect()),
+                    entries,
+                }
+            })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Arbitrary for TwoPhaseSet<T>
+where
+    T: Arbitrary + Ord + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<(SetLattice<T>, SetLattice<T>)>()
+            .prop_map(|(added, removed)| TwoPhaseSet { added, removed })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V> Arbitrary for TombstoneMap<K, V>
+where
+    K: Arbitrary + Ord + 'static,
+    V: Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        // no live key may be retired.
+        any::<(MapLattice<K, V>, SetLattice<K>)>()
+            .prop_map(|(mut live, retired)| {
+                live.retain(|(k, _)| !retired.contains(k));
+                TombstoneMap { live, retired }
+            })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A> Arbitrary for Position<A>
+where
+    A: Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        proptest::collection::vec((0..4u64, any::<A>()), 1..4)
+            .prop_map(|inner| Position { inner })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, T> Arbitrary for Sequence<A, T>
+where
+    A: Arbitrary + Ord + 'static,
+    T: Arbitrary + Clone + PartialEq + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<MapLattice<Position<A>, Redactable<T>>>()
+            .prop_map(|inner| Sequence { inner })
+            .boxed()
+    }
+}
+
+// Check that a borrowed join agrees with the join.
+#[cfg(test)]
+fn check_join_ref<S>()
+where
+    S: JoinRef + Arbitrary + fmt::Debug,
+{
+    let config = Config {
+        failure_persistence: None,
+        ..Config::default()
+    };
+
+    TestRunner::new(config)
+        .run(&any::<(S, S)>(), |(a, b)| {
+            let mut joined = a.clone();
+            joined.join_assign_ref(&b);
+            prop_assert_eq!(joined, a.join(b));
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn check_builtins() {
+    check_laws::<()>().unwrap();
+    check_laws::<Max<u8>>().unwrap();
+    check_laws::<Min<i16>>().unwrap();
+    check_laws::<Interval<i8>>().unwrap();
+    check_laws::<Pair<Max<u8>, Min<u8>>>().unwrap();
+    // the laws only hold for totally ordered guards.
+    check_laws::<GuardedPair<Min<u8>, Max<u8>>>().unwrap();
+    check_laws::<Flat<u8>>().unwrap();
+    check_laws::<Redactable<bool>>().unwrap();
+    check_laws::<Hlc>().unwrap();
+    check_laws::<Lww<bool, u8>>().unwrap();
+    check_laws::<Toggle<bool>>().unwrap();
+    check_laws::<BitSetLattice<[u64; 1]>>().unwrap();
+    check_laws::<ArrayMapLattice<u8, Max<u8>, 3>>().unwrap();
+    check_laws::<ArraySetLattice<u8, 3>>().unwrap();
+    check_laws::<ArrayVecLattice<Max<u8>, 3>>().unwrap();
+    check_laws::<Maximum<(u8, bool)>>().unwrap();
+    check_laws::<Minimum<u8>>().unwrap();
+    check_laws::<Max<TotalFloat<f64>>>().unwrap();
+    check_laws::<bool>().unwrap();
+    check_laws::<Option<Max<u8>>>().unwrap();
+    check_laws::<(Redactable<bool>, Max<u8>, bool)>().unwrap();
+    check_laws::<[Redactable<bool>; 3]>().unwrap();
+
+    #[cfg(feature = "alloc")]
+    {
+        check_laws::<MapLattice<u8, Max<u8>>>().unwrap();
+        check_laws::<SetLattice<u8>>().unwrap();
+        check_laws::<BitSetLattice<alloc::vec::Vec<u64>>>().unwrap();
+        check_laws::<VecLattice<Max<u8>>>().unwrap();
+        check_laws::<VecLattice<Redactable<bool>>>().unwrap();
+        check_laws::<MapLattice<bool, SetLattice<u8>>>().unwrap();
+        check_laws::<PNCounter<u8>>().unwrap();
+        check_laws::<VersionVector<bool>>().unwrap();
+        check_laws::<MvRegister<bool, bool>>().unwrap();
+        check_laws::<OrSet<bool, u8>>().unwrap();
+        check_laws::<OrMap<bool, bool, Max<u8>>>().unwrap();
+        check_laws::<TwoPhaseSet<u8>>().unwrap();
+        check_laws::<Sequence<bool, bool>>().unwrap();
+        check_laws::<alloc::boxed::Box<Max<u8>>>().unwrap();
+        check_laws::<TombstoneMap<u8, Max<u8>>>().unwrap();
+
+        check_join_ref::<MapLattice<u8, Max<u8>>>();
+        check_join_ref::<MapLattice<bool, SetLattice<u8>>>();
+        check_join_ref::<SetLattice<u8>>();
+        check_join_ref::<VecLattice<Redactable<bool>>>();
+        check_join_ref::<Option<VecLattice<Max<u8>>>>();
+        check_join_ref::<PNCounter<u8>>();
+        check_join_ref::<TwoPhaseSet<u8>>();
+        check_join_ref::<Sequence<bool, bool>>();
+    }
+}
@@ -0,0 +1,1184 @@
+//! Collections with a fixed capacity, which need no allocator. Any join or
+//! insertion which does not fit saturates the collection to its top element,
+//! which is above every other value and absorbs all further joins.
+
+use core::{borrow::Borrow, cmp, fmt, mem, ops};
+
+use crate::{partial_ord_helper, BoundedLattice, Decompose, JoinRef, Lattice, Semilattice};
+
+/// The error returned when an insertion does not fit, after which the
+/// collection has saturated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError;
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("The collection has saturated")
+    }
+}
+
+/// A `MapLattice` of at most `N` entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayMapLattice<K, V, const N: usize> {
+    // sorted by key, with the entries before `len` all present, and none of
+    // them with a bottom value.
+    entries: [Option<(K, V)>; N],
+    len: usize,
+    overflowed: bool,
+}
+
+impl<K, V, const N: usize> ArrayMapLattice<K, V, N>
+where
+    K: Ord,
+    V: Semilattice,
+{
+    /// Join `val` into the value of `key`.
+    pub fn insert(&mut self, key: K, val: V) -> Result<(), CapacityError> {
+        if self.overflowed {
+            return Err(CapacityError);
+        }
+
+        match self.search(&key) {
+            Ok(i) => {
+                if let Some((_, v)) = &mut self.entries[i] {
+                    v.join_assign(val);
+                }
+                Ok(())
+            }
+            Err(_) if val.is_bottom() => Ok(()),
+            Err(_) if self.len == N => {
+                self.saturate();
+                Err(CapacityError)
+            }
+            Err(i) => {
+                self.entries[i..=self.len].rotate_right(1);
+                self.entries[i] = Some((key, val));
+                self.len += 1;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn entry<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let i = self.search(key).ok()?;
+        self.entries[i].as_ref().map(|(_, v)| v)
+    }
+
+    // The index of `key` among the entries, or where it would be inserted.
+    fn search<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.entries[..self.len].binary_search_by(|entry| {
+            entry
+                .as_ref()
+                .map_or(cmp::Ordering::Greater, |(k, _)| k.borrow().cmp(key))
+        })
+    }
+}
+
+impl<K, V, const N: usize> ArrayMapLattice<K, V, N> {
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().flatten().map(|(k, v)| (k, v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0 && !self.overflowed
+    }
+
+    /// Whether an insertion or join did not fit, leaving the top element.
+    pub fn is_overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    fn saturate(&mut self) {
+        *self = Self {
+            overflowed: true,
+            ..Self::default()
+        };
+    }
+
+    // Append an entry which is greater than the others.
+    fn push(&mut self, entry: (K, V)) -> Result<(), CapacityError> {
+        if self.len == N {
+            self.saturate();
+            return Err(CapacityError);
+        }
+
+        self.entries[self.len] = Some(entry);
+        self.len += 1;
+        Ok(())
+    }
+}
+
+impl<K, V, const N: usize> Default for ArrayMapLattice<K, V, N> {
+    fn default() -> Self {
+        Self {
+            entries: core::array::from_fn(|_| None),
+            len: 0,
+            overflowed: false,
+        }
+    }
+}
+
+/// Collect the entries, saturating if there are too many.
+impl<K, V, const N: usize> FromIterator<(K, V)> for ArrayMapLattice<K, V, N>
+where
+    K: Ord,
+    V: Semilattice,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::default();
+        // an error leaves the map saturated.
+        let _ = iter.into_iter().try_for_each(|(k, v)| map.insert(k, v));
+        map
+    }
+}
+
+impl<K, V, const N: usize> PartialOrd for ArrayMapLattice<K, V, N>
+where
+    K: Ord,
+    V: Semilattice,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self.overflowed, other.overflowed) {
+            (true, true) => return Some(cmp::Ordering::Equal),
+            (true, false) => return Some(cmp::Ordering::Greater),
+            (false, true) => return Some(cmp::Ordering::Less),
+            (false, false) => (),
+        }
+
+        let mut greater = false;
+        let mut less = false;
+        let mut other = other.iter().peekable();
+
+        for (k, v) in self.iter() {
+            while other.next_if(|(k2, _)| *k2 < k).is_some() {
+                // self is missing a key in other
+                less = true;
+            }
+
+            match other.next_if(|(k2, _)| *k2 == k) {
+                Some((_, v2)) => match v.partial_cmp(v2) {
+                    Some(cmp::Ordering::Less) => less = true,
+                    Some(cmp::Ordering::Greater) => greater = true,
+                    Some(cmp::Ordering::Equal) => (),
+                    None => return None,
+                },
+                // other is missing a key from self
+                None => greater = true,
+            }
+
+            if greater && less {
+                return None;
+            }
+        }
+
+        less |= other.next().is_some();
+
+        if greater && less {
+            None
+        } else {
+            Some(greater.cmp(&less))
+        }
+    }
+}
+
+impl<K, V, const N: usize> Semilattice for ArrayMapLattice<K, V, N>
+where
+    K: Ord,
+    V: Semilattice,
+{
+    fn join(self, other: Self) -> Self {
+        if self.overflowed || other.overflowed {
+            return Self::top();
+        }
+
+        // a single pass over both sorted arrays.
+        let mut map = Self::default();
+        let mut other = other.entries.into_iter().flatten().peekable();
+
+        for (k, mut v) in self.entries.into_iter().flatten() {
+            while let Some(entry) = other.next_if(|(k2, _)| *k2 < k) {
+                if map.push(entry).is_err() {
+                    return map;
+                }
+            }
+            if let Some((_, v2)) = other.next_if(|(k2, _)| *k2 == k) {
+                v.join_assign(v2);
+            }
+            if map.push((k, v)).is_err() {
+                return map;
+            }
+        }
+
+        for entry in other {
+            if map.push(entry).is_err() {
+                return map;
+            }
+        }
+
+        map
+    }
+
+    fn delta(self, since: &Self) -> Self {
+        if self.leq(since) {
+            return Self::default();
+        }
+
+        if self.overflowed {
+            return self;
+        }
+
+        // keep keys which are missing from `since`, and the deltas of those
+        // which have grown.
+        let mut map = Self::default();
+
+        for (k, v) in self.entries.into_iter().flatten() {
+            let entry = match since.entry(&k) {
+                None => (k, v),
+                Some(s) if v.leq(s) => continue,
+                Some(s) => (k, v.delta(s)),
+            };
+
+            // the delta has no more entries than `self`.
+            let _ = map.push(entry);
+        }
+
+        map
+    }
+}
+
+impl<K, V, const N: usize> JoinRef for ArrayMapLattice<K, V, N>
+where
+    K: Ord + Clone,
+    V: Semilattice + Clone,
+{
+}
+
+impl<K, V, const N: usize> Lattice for ArrayMapLattice<K, V, N>
+where
+    K: Ord,
+    V: Lattice,
+{
+    fn meet(self, other: Self) -> Self {
+        if self.overflowed {
+            return other;
+        }
+        if other.overflowed {
+            return self;
+        }
+
+        // only keys in both maps, with the meet of their values.
+        let mut map = Self::default();
+        let mut other = other.entries.into_iter().flatten().peekable();
+
+        for (k, v) in self.entries.into_iter().flatten() {
+            while other.next_if(|(k2, _)| *k2 < k).is_some() {}
+            if let Some((_, v2)) = other.next_if(|(k2, _)| *k2 == k) {
+                let v = v.meet(v2);
+                if !v.is_bottom() {
+                    // the meet has no more entries than `self`.
+                    let _ = map.push((k, v));
+                }
+            }
+        }
+
+        map
+    }
+}
+
+impl<K, V, const N: usize> BoundedLattice for ArrayMapLattice<K, V, N>
+where
+    K: Ord,
+    V: Semilattice,
+{
+    fn top() -> Self {
+        let mut map = Self::default();
+        map.saturate();
+        map
+    }
+
+    fn is_top(&self) -> bool {
+        self.overflowed
+    }
+}
+
+impl<K, V, const N: usize> Decompose for ArrayMapLattice<K, V, N>
+where
+    K: Ord + Clone,
+    V: Decompose,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        // the top element is join-irreducible.
+        if self.overflowed {
+            return func(self);
+        }
+
+        let singleton = |k, v| {
+            let mut map = Self::default();
+            let _ = map.push((k, v));
+            map
+        };
+
+        for (k, v) in self.entries.into_iter().flatten() {
+            v.decompose(|part| func(singleton(k.clone(), part)));
+        }
+    }
+}
+
+/// A `SetLattice` of at most `N` elements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArraySetLattice<T, const N: usize> {
+    // sorted, with the elements before `len` all present.
+    elements: [Option<T>; N],
+    len: usize,
+    overflowed: bool,
+}
+
+impl<T, const N: usize> ArraySetLattice<T, N>
+where
+    T: Ord,
+{
+    pub fn insert(&mut self, val: T) -> Result<(), CapacityError> {
+        if self.overflowed {
+            return Err(CapacityError);
+        }
+
+        match self.search(&val) {
+            Ok(_) => Ok(()),
+            Err(_) if self.len == N => {
+                self.saturate();
+                Err(CapacityError)
+            }
+            Err(i) => {
+                self.elements[i..=self.len].rotate_right(1);
+                self.elements[i] = Some(val);
+                self.len += 1;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn contains<Q>(&self, val: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.search(val).is_ok()
+    }
+
+    // The index of `val` among the elements, or where it would be inserted.
+    fn search<Q>(&self, val: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.elements[..self.len].binary_search_by(|element| {
+            element
+                .as_ref()
+                .map_or(cmp::Ordering::Greater, |v| v.borrow().cmp(val))
+        })
+    }
+}
+
+impl<T, const N: usize> ArraySetLattice<T, N> {
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.elements.iter().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0 && !self.overflowed
+    }
+
+    /// Whether an insertion or join did not fit, leaving the top element.
+    pub fn is_overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    fn saturate(&mut self) {
+        *self = Self {
+            overflowed: true,
+            ..Self::default()
+        };
+    }
+
+    // Append an element which is greater than the others.
+    fn push(&mut self, val: T) -> Result<(), CapacityError> {
+        if self.len == N {
+            self.saturate();
+            return Err(CapacityError);
+        }
+
+        self.elements[self.len] = Some(val);
+        self.len += 1;
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> Default for ArraySetLattice<T, N> {
+    fn default() -> Self {
+        Self {
+            elements: core::array::from_fn(|_| None),
+            len: 0,
+            overflowed: false,
+        }
+    }
+}
+
+/// Collect the elements, saturating if there are too many.
+impl<T, const N: usize> FromIterator<T> for ArraySetLattice<T, N>
+where
+    T: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::default();
+        // an error leaves the set saturated.
+        let _ = iter.into_iter().try_for_each(|v| set.insert(v));
+        set
+    }
+}
+
+impl<T, const N: usize> PartialOrd for ArraySetLattice<T, N>
+where
+    T: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self.leq(other), other.leq(self)) {
+            (true, true) => Some(cmp::Ordering::Equal),
+            (true, false) => Some(cmp::Ordering::Less),
+            (false, true) => Some(cmp::Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+impl<T, const N: usize> Semilattice for ArraySetLattice<T, N>
+where
+    T: Ord,
+{
+    fn join(self, other: Self) -> Self {
+        if self.overflowed || other.overflowed {
+            return Self::top();
+        }
+
+        // a single pass over both sorted arrays.
+        let mut set = Self::default();
+        let mut other = other.elements.into_iter().flatten().peekable();
+
+        for v in self.elements.into_iter().flatten() {
+            while let Some(v2) = other.next_if(|v2| *v2 < v) {
+                if set.push(v2).is_err() {
+                    return set;
+                }
+            }
+            other.next_if_eq(&v);
+            if set.push(v).is_err() {
+                return set;
+            }
+        }
+
+        for v in other {
+            if set.push(v).is_err() {
+                return set;
+            }
+        }
+
+        set
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        other.overflowed || (!self.overflowed && self.iter().all(|v| other.contains(v)))
+    }
+
+    fn is_bottom(&self) -> bool {
+        self.is_empty()
+    }
+
+    fn delta(self, since: &Self) -> Self {
+        if self.leq(since) {
+            return Self::default();
+        }
+
+        if self.overflowed {
+            return self;
+        }
+
+        // the elements which are missing from `since`.
+        let mut set = Self::default();
+
+        for v in self.elements.into_iter().flatten() {
+            if !since.contains(&v) {
+                // the delta has no more elements than `self`.
+                let _ = set.push(v);
+            }
+        }
+
+        set
+    }
+}
+
+impl<T, const N: usize> JoinRef for ArraySetLattice<T, N> where T: Ord + Clone {}
+
+impl<T, const N: usize> Lattice for ArraySetLattice<T, N>
+where
+    T: Ord,
+{
+    fn meet(self, other: Self) -> Self {
+        if self.overflowed {
+            return other;
+        }
+        if other.overflowed {
+            return self;
+        }
+
+        // only elements in both sets.
+        let mut set = Self::default();
+        let mut other = other.elements.into_iter().flatten().peekable();
+
+        for v in self.elements.into_iter().flatten() {
+            while other.next_if(|v2| *v2 < v).is_some() {}
+            if other.next_if_eq(&v).is_some() {
+                // the meet has no more elements than `self`.
+                let _ = set.push(v);
+            }
+        }
+
+        set
+    }
+}
+
+impl<T, const N: usize> BoundedLattice for ArraySetLattice<T, N>
+where
+    T: Ord,
+{
+    fn top() -> Self {
+        let mut set = Self::default();
+        set.saturate();
+        set
+    }
+
+    fn is_top(&self) -> bool {
+        self.overflowed
+    }
+}
+
+impl<T, const N: usize> Decompose for ArraySetLattice<T, N>
+where
+    T: Ord,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        // the top element is join-irreducible.
+        if self.overflowed {
+            return func(self);
+        }
+
+        for v in self.elements.into_iter().flatten() {
+            let mut set = Self::default();
+            let _ = set.push(v);
+            func(set)
+        }
+    }
+}
+
+/// A `VecLattice` of at most `N` elements. Joins never overflow, but pushing
+/// or growing past `N` elements does.
+#[derive(Debug, Clone)]
+pub struct ArrayVecLattice<T, const N: usize> {
+    // the elements from `len` onwards are bottom.
+    inner: [T; N],
+    len: usize,
+    overflowed: bool,
+}
+
+impl<T, const N: usize> ArrayVecLattice<T, N>
+where
+    T: Semilattice,
+{
+    pub fn push(&mut self, v: T) -> Result<(), CapacityError> {
+        *self.entry_mut(self.len as u64)? = v;
+        Ok(())
+    }
+
+    pub fn entry(&self, key: u64) -> Option<&T> {
+        self.get(key as usize)
+    }
+
+    /// The element at `key`, growing to include it if needed.
+    pub fn entry_mut(&mut self, key: u64) -> Result<&mut T, CapacityError> {
+        if self.overflowed {
+            return Err(CapacityError);
+        }
+
+        if key as usize >= N {
+            *self = Self::top();
+            return Err(CapacityError);
+        }
+
+        self.len = self.len.max(key as usize + 1);
+        Ok(&mut self.inner[key as usize])
+    }
+
+    /// Whether a push or growth did not fit, leaving the top element.
+    pub fn is_overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// Remove the trailing bottom elements, such as those left behind by
+    /// `entry_mut`, which are already treated as missing.
+    pub fn normalize(&mut self) {
+        self.len = self.present().len();
+    }
+
+    // The elements up to the last one which is not bottom.
+    fn present(&self) -> &[T] {
+        let bottom = self.iter().rev().take_while(|x| x.is_bottom());
+        &self.inner[..self.len - bottom.count()]
+    }
+}
+
+impl<T, const N: usize> ops::Deref for ArrayVecLattice<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner[..self.len]
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVecLattice<T, N>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self {
+            inner: core::array::from_fn(|_| T::default()),
+            len: 0,
+            overflowed: false,
+        }
+    }
+}
+
+impl<T, const N: usize> PartialOrd for ArrayVecLattice<T, N>
+where
+    T: Semilattice,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self.overflowed, other.overflowed) {
+            (true, true) => Some(cmp::Ordering::Equal),
+            (true, false) => Some(cmp::Ordering::Greater),
+            (false, true) => Some(cmp::Ordering::Less),
+            (false, false) => {
+                let (a, b) = (self.present(), other.present());
+
+                partial_ord_helper([
+                    a.len().partial_cmp(&b.len()),
+                    partial_ord_helper(a.iter().zip(b).map(|(a, b)| a.partial_cmp(b))),
+                ])
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> PartialEq for ArrayVecLattice<T, N>
+where
+    T: Semilattice,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.overflowed == other.overflowed && self.present() == other.present()
+    }
+}
+
+impl<T, const N: usize> Semilattice for ArrayVecLattice<T, N>
+where
+    T: Semilattice,
+{
+    fn join(mut self, other: Self) -> Self {
+        if self.overflowed || other.overflowed {
+            return Self::top();
+        }
+
+        // the elements past the end of either are bottom.
+        for (l, r) in self.inner.iter_mut().zip(other.inner) {
+            l.join_assign(r);
+        }
+        self.len = self.len.max(other.len);
+
+        self
+    }
+
+    fn delta(mut self, since: &Self) -> Self {
+        if self.leq(since) {
+            return Self::default();
+        }
+
+        if self.overflowed {
+            return self;
+        }
+
+        for (l, r) in self.inner.iter_mut().zip(&since.inner) {
+            *l = mem::take(l).delta(r);
+        }
+
+        self.normalize();
+        self
+    }
+}
+
+impl<T, const N: usize> JoinRef for ArrayVecLattice<T, N> where T: Semilattice + Clone {}
+
+impl<T, const N: usize> BoundedLattice for ArrayVecLattice<T, N>
+where
+    T: Semilattice,
+{
+    fn top() -> Self {
+        Self {
+            overflowed: true,
+            ..Self::default()
+        }
+    }
+
+    fn is_top(&self) -> bool {
+        self.overflowed
+    }
+}
+
+impl<T, const N: usize> Decompose for ArrayVecLattice<T, N>
+where
+    T: Decompose,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        // the top element is join-irreducible.
+        if self.overflowed {
+            return func(self);
+        }
+
+        let len = self.len;
+
+        for (i, v) in self.inner.into_iter().take(len).enumerate() {
+            v.decompose(|part| {
+                let mut vec = Self::default();
+                vec.inner[i] = part;
+                vec.len = i + 1;
+                func(vec)
+            });
+        }
+    }
+}
+
+/// Encoded as null when saturated, and otherwise like `MapLattice`.
+#[cfg(feature = "minicbor")]
+impl<K, V, const N: usize> minicbor::Encode for ArrayMapLattice<K, V, N>
+where
+    K: minicbor::Encode,
+    V: minicbor::Encode,
+{
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        encoding::encode(self.overflowed, self.len, self.entries.iter().flatten(), e)
+    }
+}
+
+#[cfg(feature = "minicbor")]
+impl<'b, K, V, const N: usize> minicbor::Decode<'b> for ArrayMapLattice<K, V, N>
+where
+    K: Ord + minicbor::Decode<'b>,
+    V: Semilattice + minicbor::Decode<'b>,
+{
+    fn decode(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        encoding::decode(d, |map: &mut Self, (k, v)| map.insert(k, v))
+    }
+}
+
+/// Encoded as null when saturated, and otherwise like `SetLattice`.
+#[cfg(feature = "minicbor")]
+impl<T, const N: usize> minicbor::Encode for ArraySetLattice<T, N>
+where
+    T: minicbor::Encode,
+{
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        encoding::encode(self.overflowed, self.len, self.iter().map(|v| (v, ())), e)
+    }
+}
+
+#[cfg(feature = "minicbor")]
+impl<'b, T, const N: usize> minicbor::Decode<'b> for ArraySetLattice<T, N>
+where
+    T: Ord + minicbor::Decode<'b>,
+{
+    fn decode(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        encoding::decode(d, |set: &mut Self, (v, ())| set.insert(v))
+    }
+}
+
+/// Encoded as null when saturated, and otherwise like `VecLattice`.
+#[cfg(feature = "minicbor")]
+impl<T, const N: usize> minicbor::Encode for ArrayVecLattice<T, N>
+where
+    T: Semilattice + minicbor::Encode,
+{
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        let present = self.present();
+        encoding::encode(self.overflowed, present.len(), present, e)
+    }
+}
+
+#[cfg(feature = "minicbor")]
+impl<'b, T, const N: usize> minicbor::Decode<'b> for ArrayVecLattice<T, N>
+where
+    T: Semilattice + minicbor::Decode<'b>,
+{
+    fn decode(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        encoding::decode(d, Self::push)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V, const N: usize> serde::Serialize for ArrayMapLattice<K, V, N>
+where
+    K: serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        encoding::serialize(self.overflowed, self.entries.iter().flatten(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V, const N: usize> serde::Deserialize<'de> for ArrayMapLattice<K, V, N>
+where
+    K: Ord + serde::Deserialize<'de>,
+    V: Semilattice + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        encoding::deserialize(deserializer, |map: &mut Self, (k, v)| map.insert(k, v))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const N: usize> serde::Serialize for ArraySetLattice<T, N>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let elements = self.elements.iter().flatten().map(|v| (v, ()));
+        encoding::serialize(self.overflowed, elements, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const N: usize> serde::Deserialize<'de> for ArraySetLattice<T, N>
+where
+    T: Ord + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        encoding::deserialize(deserializer, |set: &mut Self, (v, ())| set.insert(v))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const N: usize> serde::Serialize for ArrayVecLattice<T, N>
+where
+    T: Semilattice + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        encoding::serialize(self.overflowed, self.present(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const N: usize> serde::Deserialize<'de> for ArrayVecLattice<T, N>
+where
+    T: Semilattice + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        encoding::deserialize(deserializer, Self::push)
+    }
+}
+
+// The encodings shared by the collections: nothing when saturated, and
+// otherwise their items, which are pushed one at a time when decoding.
+#[cfg(any(feature = "minicbor", feature = "serde"))]
+mod encoding {
+    use crate::{BoundedLattice, CapacityError};
+
+    #[cfg(feature = "minicbor")]
+    pub fn encode<T, W>(
+        overflowed: bool,
+        len: usize,
+        items: impl IntoIterator<Item = T>,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>>
+    where
+        T: minicbor::Encode,
+        W: minicbor::encode::Write,
+    {
+        if overflowed {
+            return e.null()?.ok();
+        }
+
+        e.array(len as u64)?;
+        for item in items {
+            e.encode(item)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "minicbor")]
+    pub fn decode<'b, S, T>(
+        d: &mut minicbor::Decoder<'b>,
+        mut push: impl FnMut(&mut S, T) -> Result<(), CapacityError>,
+    ) -> Result<S, minicbor::decode::Error>
+    where
+        S: BoundedLattice,
+        T: minicbor::Decode<'b>,
+    {
+        if d.datatype()? == minicbor::data::Type::Null {
+            d.skip()?;
+            return Ok(S::top());
+        }
+
+        let mut collection = S::default();
+
+        for item in d.array_iter()? {
+            push(&mut collection, item?)
+                .map_err(|_| minicbor::decode::Error::Message("Too many items"))?;
+        }
+
+        Ok(collection)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn serialize<T, S>(
+        overflowed: bool,
+        items: impl IntoIterator<Item = T> + Clone,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize,
+        S: serde::Serializer,
+    {
+        struct Items<I>(I);
+
+        impl<I> serde::Serialize for Items<I>
+        where
+            I: IntoIterator + Clone,
+            I::Item: serde::Serialize,
+        {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.clone())
+            }
+        }
+
+        if overflowed {
+            serializer.serialize_none()
+        } else {
+            serializer.serialize_some(&Items(items))
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn deserialize<'de, S, T, D>(
+        deserializer: D,
+        push: impl FnMut(&mut S, T) -> Result<(), CapacityError>,
+    ) -> Result<S, D::Error>
+    where
+        S: BoundedLattice,
+        T: serde::Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        use core::{fmt, marker::PhantomData};
+        use serde::de::{self, Error};
+
+        struct Visitor<S, T, P>(P, PhantomData<(S, T)>);
+
+        impl<'de, S, T, P> de::Visitor<'de> for Visitor<S, T, P>
+        where
+            S: BoundedLattice,
+            T: serde::Deserialize<'de>,
+            P: FnMut(&mut S, T) -> Result<(), CapacityError>,
+        {
+            type Value = S;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a sequence, or none when saturated")
+            }
+
+            fn visit_none<E: Error>(self) -> Result<S, E> {
+                Ok(S::top())
+            }
+
+            fn visit_some<D: serde::Deserializer<'de>>(self, d: D) -> Result<S, D::Error> {
+                d.deserialize_seq(self)
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(mut self, mut seq: A) -> Result<S, A::Error> {
+                let mut collection = S::default();
+                let mut len = 0;
+
+                while let Some(item) = seq.next_element()? {
+                    len += 1;
+                    (self.0)(&mut collection, item)
+                        .map_err(|_| A::Error::invalid_length(len, &"fewer items"))?;
+                }
+
+                Ok(collection)
+            }
+        }
+
+        deserializer.deserialize_option(Visitor(push, PhantomData))
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{
+        partially_verify_decomposition, partially_verify_distributive_lattice_laws,
+        partially_verify_lattice_laws, partially_verify_semilattice_laws, Max,
+    };
+
+    let a = ArrayMapLattice::<_, _, 3>::from_iter([("Alice", Max(123)), ("Bob", Max(50))]);
+    let b = ArrayMapLattice::from_iter([("Bob", Max(300)), ("Carol", Max(100))]);
+    let c = ArrayMapLattice::from_iter([("Carol", Max(150))]);
+
+    let d = a.clone().join(b.clone()).join(c.clone());
+    assert!(d.iter().eq([
+        (&"Alice", &Max(123)),
+        (&"Bob", &Max(300)),
+        (&"Carol", &Max(150))
+    ]));
+    assert_eq!(
+        d.clone().delta(&a),
+        ArrayMapLattice::from_iter([("Bob", Max(300)), ("Carol", Max(150))])
+    );
+    assert_eq!(
+        a.clone().meet(b.clone()),
+        ArrayMapLattice::from_iter([("Bob", Max(50))])
+    );
+
+    // a fourth key does not fit.
+    let mut e = d.clone();
+    assert_eq!(e.insert("Carol", Max(200)), Ok(()));
+    assert_eq!(e.insert("Dave", Max(1)), Err(CapacityError));
+    assert!(e.is_overflowed() && e.is_top() && e.entry("Alice").is_none());
+    assert!(a
+        .clone()
+        .join(ArrayMapLattice::from_iter([
+            ("Dave", Max(1)),
+            ("Eve", Max(2))
+        ]))
+        .is_top());
+
+    partially_verify_distributive_lattice_laws([a.clone(), b.clone(), c.clone(), d.clone()]);
+    partially_verify_lattice_laws([a.clone(), b.clone(), c.clone(), d.clone(), e.clone()]);
+    partially_verify_decomposition([a, b, c, d, e]);
+
+    let f = ArraySetLattice::<_, 2>::from_iter([1, 2]);
+    let g = ArraySetLattice::from_iter([2]);
+    let h = ArraySetLattice::from_iter([3]);
+    assert!(f.contains(&2) && !f.contains(&3));
+    assert!(g.clone().join(h.clone()).iter().eq(&[2, 3]));
+    assert!(f.clone().join(h.clone()).is_top());
+    partially_verify_lattice_laws([f.clone(), g.clone(), h.clone()]);
+    partially_verify_decomposition([f, g, h]);
+
+    let mut i = ArrayVecLattice::<_, 3>::default();
+    i.push(Max(1)).unwrap();
+    i.push(Max(5)).unwrap();
+    let mut j = ArrayVecLattice::default();
+    *j.entry_mut(2).unwrap() = Max(7);
+    assert_eq!(&*i.clone().join(j.clone()), &[Max(1), Max(5), Max(7)]);
+    assert_eq!(j.entry_mut(3), Err(CapacityError));
+    assert!(j.is_overflowed() && j.entry(0).is_none());
+    partially_verify_semilattice_laws([i.clone(), j.clone(), ArrayVecLattice::default()]);
+    partially_verify_decomposition([i, j]);
+
+    // encoded like the growable collections, or as null once saturated.
+    #[cfg(all(feature = "minicbor", feature = "alloc"))]
+    {
+        use crate::MapLattice;
+        use alloc::vec::Vec;
+
+        fn to_vec(x: impl minicbor::Encode) -> Result<Vec<u8>, ()> {
+            let mut bytes = Vec::new();
+            minicbor::encode(x, &mut bytes).map_err(|_| ())?;
+            Ok(bytes)
+        }
+
+        let a = ArrayMapLattice::<_, _, 2>::from_iter([(1u8, Max(2u8)), (3, Max(4))]);
+        let bytes = to_vec(&a).unwrap();
+        assert_eq!(
+            bytes,
+            to_vec(MapLattice::from_iter([(1u8, Max(2u8)), (3, Max(4))])).unwrap()
+        );
+        assert_eq!(
+            minicbor::decode::<ArrayMapLattice<_, _, 2>>(&bytes).unwrap(),
+            a
+        );
+        assert!(minicbor::decode::<ArrayMapLattice<u8, Max<u8>, 1>>(&bytes).is_err());
+
+        let top = ArrayVecLattice::<Max<u8>, 2>::top();
+        let bytes = to_vec(&top).unwrap();
+        assert_eq!(bytes, [0xf6]);
+        assert_eq!(
+            minicbor::decode::<ArrayVecLattice<_, 2>>(&bytes).unwrap(),
+            top
+        );
+    }
+}
+
+/// A datalog iteration over the fixed capacity collections needs no
+/// allocator, and stops once they saturate.
+#[test]
+fn check_iteration() {
+    use crate::{DeferredRestore, Iteration, Simple};
+
+    // the powers of two modulo `m`.
+    let powers = |m: u8| {
+        let mut powers = Simple::<ArraySetLattice<u8, 4>>::default();
+        powers.insert(ArraySetLattice::from_iter([1]));
+
+        let mut iteration = Iteration::new(usize::MAX);
+        while iteration.unfinished() && !powers.is_saturated() {
+            let mut powers = iteration.guard(&mut powers);
+
+            let mut next = ArraySetLattice::default();
+            powers.for_each_recent(|set| {
+                for n in set.iter() {
+                    let _ = next.insert(n * 2 % m);
+                }
+            });
+            powers.insert(next);
+        }
+
+        powers
+    };
+
+    let mut found = false;
+    powers(7).for_each_stable(|set| found = set.iter().eq(&[1, 2, 4]));
+    assert!(found);
+
+    // there are more than four powers of two modulo 11.
+    assert!(powers(11).is_saturated());
+}
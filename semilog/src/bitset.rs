@@ -0,0 +1,435 @@
+use core::cmp::Ordering;
+
+use crate::{partial_ord_helper, BoundedLattice, Decompose, JoinRef, Lattice, Semilattice};
+
+/// The words of a `BitSetLattice`, which are either a fixed size array or a
+/// growable vector. Missing words are zero.
+pub trait Words: AsRef<[u64]> + AsMut<[u64]> {
+    fn zeroed() -> Self;
+
+    /// Grow to at least `len` words, returning whether there is room.
+    fn grow(&mut self, len: usize) -> bool;
+
+    /// Call `func` with the words as little-endian bytes, without trailing
+    /// zeros.
+    fn with_bytes<R>(&self, func: impl FnOnce(&[u8]) -> R) -> R;
+}
+
+impl<const N: usize> Words for [u64; N] {
+    fn zeroed() -> Self {
+        [0; N]
+    }
+
+    fn grow(&mut self, len: usize) -> bool {
+        len <= N
+    }
+
+    fn with_bytes<R>(&self, func: impl FnOnce(&[u8]) -> R) -> R {
+        let mut bytes = [[0; 8]; N];
+
+        for (bytes, word) in bytes.iter_mut().zip(self) {
+            *bytes = word.to_le_bytes();
+        }
+
+        func(&bytes.as_flattened()[..byte_len(self)])
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Words for alloc::vec::Vec<u64> {
+    fn zeroed() -> Self {
+        Self::new()
+    }
+
+    fn grow(&mut self, len: usize) -> bool {
+        if self.len() < len {
+            self.resize(len, 0);
+        }
+
+        true
+    }
+
+    fn with_bytes<R>(&self, func: impl FnOnce(&[u8]) -> R) -> R {
+        let bytes: alloc::vec::Vec<u8> = self.iter().flat_map(|w| w.to_le_bytes()).collect();
+        func(&bytes[..byte_len(self)])
+    }
+}
+
+// The number of bytes up to and including the last non-zero one.
+fn byte_len(words: &[u64]) -> usize {
+    words
+        .iter()
+        .rposition(|w| *w != 0)
+        .map_or(0, |i| i * 8 + 8 - words[i].leading_zeros() as usize / 8)
+}
+
+/// A set of small integers, stored densely as one bit each. Joins are a
+/// word-wise or, and sets are ordered by inclusion. The words are either
+/// `[u64; N]`, for at most `64 * N` elements, or a growable `Vec<u64>`.
+#[derive(Debug, Clone)]
+pub struct BitSetLattice<W> {
+    pub inner: W,
+}
+
+impl<W> BitSetLattice<W>
+where
+    W: Words,
+{
+    /// Insert `bit`, which must fit in a fixed size set.
+    pub fn insert(&mut self, bit: usize) {
+        assert!(self.inner.grow(bit / 64 + 1), "Bit {} is out of range", bit);
+
+        self.inner.as_mut()[bit / 64] |= 1 << (bit % 64);
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        self.inner
+            .as_ref()
+            .get(bit / 64)
+            .is_some_and(|w| w & 1 << (bit % 64) != 0)
+    }
+
+    /// The elements in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.inner
+            .as_ref()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, w)| Ones(*w).map(move |bit| i * 64 + bit))
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner
+            .as_ref()
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.as_ref().iter().all(|w| *w == 0)
+    }
+
+    // Set the `i`th byte of the little-endian words, returning whether there
+    // is room.
+    #[cfg(any(feature = "minicbor", feature = "serde"))]
+    fn set_byte(&mut self, i: usize, byte: u8) -> bool {
+        if byte == 0 {
+            return true;
+        }
+
+        if !self.inner.grow(i / 8 + 1) {
+            return false;
+        }
+
+        self.inner.as_mut()[i / 8] |= (byte as u64) << (i % 8 * 8);
+        true
+    }
+}
+
+// The set bits of a word.
+struct Ones(u64);
+
+impl Iterator for Ones {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let bit = self.0.trailing_zeros() as usize;
+        self.0 &= self.0.checked_sub(1)?;
+        Some(bit)
+    }
+}
+
+// Pairs of words of `a` and `b`, where the shorter is padded with zeros.
+fn zip_words<'a>(a: &'a [u64], b: &'a [u64]) -> impl Iterator<Item = (u64, u64)> + 'a {
+    (0..a.len().max(b.len())).map(|i| {
+        (
+            a.get(i).copied().unwrap_or(0),
+            b.get(i).copied().unwrap_or(0),
+        )
+    })
+}
+
+impl<W> FromIterator<usize> for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::default();
+        iter.into_iter().for_each(|bit| set.insert(bit));
+        set
+    }
+}
+
+impl<W> Default for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn default() -> Self {
+        Self { inner: W::zeroed() }
+    }
+}
+
+impl<W> PartialEq for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn eq(&self, other: &Self) -> bool {
+        zip_words(self.inner.as_ref(), other.inner.as_ref()).all(|(a, b)| a == b)
+    }
+}
+
+impl<W> PartialOrd for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        partial_ord_helper(zip_words(self.inner.as_ref(), other.inner.as_ref()).map(
+            |(a, b)| match (a & !b == 0, b & !a == 0) {
+                (true, true) => Some(Ordering::Equal),
+                (true, false) => Some(Ordering::Less),
+                (false, true) => Some(Ordering::Greater),
+                (false, false) => None,
+            },
+        ))
+    }
+}
+
+impl<W> Semilattice for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn join(mut self, other: Self) -> Self {
+        self.join_assign_changed(other);
+        self
+    }
+
+    fn join_assign(&mut self, other: Self) {
+        self.join_assign_changed(other);
+    }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        let other = other.inner.as_ref();
+        let len = other.iter().rposition(|w| *w != 0).map_or(0, |i| i + 1);
+        assert!(self.inner.grow(len), "Bit sets have the same size");
+
+        let mut changed = false;
+
+        for (a, b) in self.inner.as_mut().iter_mut().zip(&other[..len]) {
+            changed |= b & !*a != 0;
+            *a |= b;
+        }
+
+        changed
+    }
+
+    fn delta(mut self, since: &Self) -> Self {
+        for (a, b) in self.inner.as_mut().iter_mut().zip(since.inner.as_ref()) {
+            *a &= !b;
+        }
+
+        self
+    }
+}
+
+impl<W> JoinRef for BitSetLattice<W> where W: Words + Clone {}
+
+impl<W> Lattice for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn meet(mut self, other: Self) -> Self {
+        let other = other.inner.as_ref();
+
+        for (i, a) in self.inner.as_mut().iter_mut().enumerate() {
+            *a &= other.get(i).copied().unwrap_or(0);
+        }
+
+        self
+    }
+}
+
+impl<const N: usize> BoundedLattice for BitSetLattice<[u64; N]> {
+    fn top() -> Self {
+        Self {
+            inner: [u64::MAX; N],
+        }
+    }
+}
+
+impl<W> Decompose for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        for bit in self.iter() {
+            let mut set = Self::default();
+            set.insert(bit);
+            func(set)
+        }
+    }
+}
+
+/// Encoded as the bytes of the little-endian words, without trailing zeros,
+/// so that fixed size and growable sets have the same encoding.
+#[cfg(feature = "minicbor")]
+impl<W> minicbor::Encode for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn encode<E: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<E>,
+    ) -> Result<(), minicbor::encode::Error<E::Error>> {
+        self.inner.with_bytes(|bytes| e.bytes(bytes))?.ok()
+    }
+}
+
+#[cfg(feature = "minicbor")]
+impl<'b, W> minicbor::Decode<'b> for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn decode(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        let mut set = Self::default();
+        let mut len = 0;
+
+        for bytes in d.bytes_iter()? {
+            let bytes = bytes?;
+
+            for (i, byte) in bytes.iter().enumerate() {
+                if !set.set_byte(len + i, *byte) {
+                    return Err(minicbor::decode::Error::Message("Bit set is too large"));
+                }
+            }
+
+            len += bytes.len();
+        }
+
+        Ok(set)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<W> serde::Serialize for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inner
+            .with_bytes(|bytes| serializer.serialize_bytes(bytes))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, W> serde::Deserialize<'de> for BitSetLattice<W>
+where
+    W: Words,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{self, Error};
+
+        struct Visitor<W>(core::marker::PhantomData<W>);
+
+        impl<'de, W> de::Visitor<'de> for Visitor<W>
+        where
+            W: Words,
+        {
+            type Value = BitSetLattice<W>;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str("the bytes of a bit set")
+            }
+
+            fn visit_bytes<E: Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+                let mut set = BitSetLattice::default();
+
+                for (i, byte) in bytes.iter().enumerate() {
+                    if !set.set_byte(i, *byte) {
+                        return Err(E::invalid_length(bytes.len(), &self));
+                    }
+                }
+
+                Ok(set)
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut set = BitSetLattice::default();
+                let mut len = 0;
+
+                while let Some(byte) = seq.next_element()? {
+                    if !set.set_byte(len, byte) {
+                        return Err(A::Error::invalid_length(len + 1, &self));
+                    }
+                    len += 1;
+                }
+
+                Ok(set)
+            }
+        }
+
+        deserializer.deserialize_bytes(Visitor(core::marker::PhantomData))
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_decomposition, partially_verify_distributive_lattice_laws};
+
+    let mut a = BitSetLattice::<[u64; 2]>::default();
+    a.insert(3);
+    a.insert(64);
+    a.insert(127);
+    assert!(a.contains(64) && !a.contains(65) && !a.contains(1000));
+    assert_eq!(a.len(), 3);
+    assert!(a.iter().eq([3, 64, 127]));
+
+    let b = BitSetLattice::from_iter([1, 3]);
+    let c = a.clone().join(b.clone());
+    assert!(c.iter().eq([1, 3, 64, 127]));
+    assert!(a < c && b < c && a.partial_cmp(&b).is_none());
+    assert!(a.clone().meet(b.clone()).iter().eq([3]));
+    assert!(c.clone().delta(&a).iter().eq([1]));
+    assert!(BitSetLattice::<[u64; 2]>::top().contains(127));
+
+    let samples = [a, b, c, BitSetLattice::default(), BitSetLattice::top()];
+    partially_verify_distributive_lattice_laws(samples.clone());
+    partially_verify_decomposition(samples);
+
+    #[cfg(feature = "alloc")]
+    {
+        use alloc::{vec, vec::Vec};
+
+        // trailing zero words are insignificant.
+        let d = BitSetLattice::<Vec<u64>>::from_iter([1, 200]);
+        let e = BitSetLattice { inner: vec![2, 0] };
+        assert_eq!(d.clone().meet(e.clone()), BitSetLattice { inner: vec![2] });
+        assert!(e < d);
+
+        let samples = [
+            d,
+            e,
+            BitSetLattice::from_iter([5, 70]),
+            BitSetLattice::default(),
+        ];
+        partially_verify_distributive_lattice_laws(samples.clone());
+        partially_verify_decomposition(samples);
+    }
+
+    #[cfg(all(feature = "minicbor", feature = "alloc"))]
+    {
+        let a = BitSetLattice::<[u64; 2]>::from_iter([0, 9, 64]);
+        let mut bytes = alloc::vec::Vec::new();
+        minicbor::encode(&a, &mut bytes).unwrap();
+        assert_eq!(bytes, [0x49, 0x01, 0x02, 0, 0, 0, 0, 0, 0, 0x01]);
+        assert_eq!(
+            minicbor::decode::<BitSetLattice<[u64; 2]>>(&bytes).unwrap(),
+            a
+        );
+        assert_eq!(
+            minicbor::decode::<BitSetLattice<alloc::vec::Vec<u64>>>(&bytes).unwrap(),
+            BitSetLattice::from_iter([0, 9, 64])
+        );
+        assert!(minicbor::decode::<BitSetLattice<[u64; 1]>>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use core::cmp;
+
+use crate::{partial_ord_helper, Decompose, JoinRef, MapLattice, Max, Semilattice};
+
+/// A grow-only counter. Each actor only increments its own entry, and the
+/// value is the sum of all entries.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct GCounter<A> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub inner: MapLattice<A, Max<u64>>,
+}
+
+impl<A> GCounter<A>
+where
+    A: Ord + Clone,
+{
+    /// Increment the entry of `actor`, which saturates at `u64::MAX`.
+    pub fn increment(&mut self, actor: A) {
+        let entry = &mut self.inner.entry_mut(&actor).0;
+        *entry = entry.saturating_add(1);
+    }
+
+    /// The sum of all entries, which saturates at `u64::MAX` rather than
+    /// overflowing.
+    pub fn value(&self) -> u64 {
+        self.inner
+            .iter()
+            .fold(0, |sum: u64, (_, v)| sum.saturating_add(v.0))
+    }
+}
+
+impl<A> Default for GCounter<A> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<A> PartialOrd for GCounter<A>
+where
+    A: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.inner.partial_cmp(&other.inner)
+    }
+}
+
+impl<A> Semilattice for GCounter<A>
+where
+    A: Ord,
+{
+    fn join(self, other: Self) -> Self {
+        Self {
+            inner: self.inner.join(other.inner),
+        }
+    }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        self.inner.join_assign_changed(other.inner)
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        self.inner.leq(&other.inner)
+    }
+
+    fn is_bottom(&self) -> bool {
+        self.inner.is_bottom()
+    }
+
+    fn delta(self, since: &Self) -> Self {
+        Self {
+            inner: self.inner.delta(&since.inner),
+        }
+    }
+}
+
+impl<A> JoinRef for GCounter<A>
+where
+    A: Ord + Clone,
+{
+    fn join_assign_ref(&mut self, other: &Self) {
+        self.inner.join_assign_ref(&other.inner)
+    }
+}
+
+impl<A> Decompose for GCounter<A>
+where
+    A: Ord + Clone,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        self.inner.decompose(|inner| func(Self { inner }))
+    }
+}
+
+/// A counter which can be both incremented and decremented, as a pair of
+/// grow-only counters.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
+pub struct PNCounter<A> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub increments: GCounter<A>,
+    #[cfg_attr(feature = "minicbor", n(1))]
+    pub decrements: GCounter<A>,
+}
+
+impl<A> PNCounter<A>
+where
+    A: Ord + Clone,
+{
+    pub fn increment(&mut self, actor: A) {
+        self.increments.increment(actor);
+    }
+
+    pub fn decrement(&mut self, actor: A) {
+        self.decrements.increment(actor);
+    }
+
+    /// The increments minus the decrements, which saturates at the bounds of
+    /// `i64` rather than wrapping around.
+    pub fn value(&self) -> i64 {
+        let value = self.increments.value() as i128 - self.decrements.value() as i128;
+        value.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+    }
+}
+
+impl<A> Default for PNCounter<A> {
+    fn default() -> Self {
+        Self {
+            increments: Default::default(),
+            decrements: Default::default(),
+        }
+    }
+}
+
+impl<A> PartialOrd for PNCounter<A>
+where
+    A: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        partial_ord_helper([
+            self.increments.partial_cmp(&other.increments),
+            self.decrements.partial_cmp(&other.decrements),
+        ])
+    }
+}
+
+impl<A> Semilattice for PNCounter<A>
+where
+    A: Ord,
+{
+    fn join(self, other: Self) -> Self {
+        Self {
+            increments: self.increments.join(other.increments),
+            decrements: self.decrements.join(other.decrements),
+        }
+    }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        self.increments.join_assign_changed(other.increments)
+            | self.decrements.join_assign_changed(other.decrements)
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        self.increments.leq(&other.increments) && self.decrements.leq(&other.decrements)
+    }
+
+    fn is_bottom(&self) -> bool {
+        self.increments.is_bottom() && self.decrements.is_bottom()
+    }
+
+    fn delta(self, since: &Self) -> Self {
+        Self {
+            increments: self.increments.delta(&since.increments),
+            decrements: self.decrements.delta(&since.decrements),
+        }
+    }
+}
+
+impl<A> JoinRef for PNCounter<A>
+where
+    A: Ord + Clone,
+{
+    fn join_assign_ref(&mut self, other: &Self) {
+        self.increments.join_assign_ref(&other.increments);
+        self.decrements.join_assign_ref(&other.decrements);
+    }
+}
+
+impl<A> Decompose for PNCounter<A>
+where
+    A: Ord + Clone,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        self.increments.decompose(|increments| {
+            func(Self {
+                increments,
+                decrements: Default::default(),
+            })
+        });
+        self.decrements.decompose(|decrements| {
+            func(Self {
+                increments: Default::default(),
+                decrements,
+            })
+        });
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_decomposition, partially_verify_semilattice_laws};
+
+    let mut a = PNCounter::default();
+    a.increment("Alice");
+    a.increment("Alice");
+    a.decrement("Bob");
+
+    let mut b = a.clone();
+    b.increment("Bob");
+    b.decrement("Alice");
+
+    let mut c = a.clone();
+    c.increment("Carol");
+    c.decrement("Carol");
+    c.decrement("Carol");
+
+    let d = b.clone().join(c.clone());
+
+    assert_eq!((a.value(), b.value(), c.value(), d.value()), (1, 1, 0, 0));
+    assert_eq!(d.increments.value(), 4);
+    assert_eq!(d.decrements.value(), 4);
+
+    // large counts saturate instead of overflowing or changing sign.
+    let mut e = PNCounter::default();
+    e.increments.inner.insert("Alice", Max(u64::MAX));
+    e.increments.inner.insert("Bob", Max(1));
+    assert_eq!((e.increments.value(), e.value()), (u64::MAX, i64::MAX));
+    e.decrements.inner.insert("Bob", Max(u64::MAX - 1));
+    assert_eq!(e.value(), 1);
+    let f = PNCounter {
+        increments: e.decrements.clone(),
+        decrements: e.increments.clone(),
+    };
+    assert_eq!(f.value(), -1);
+    let g = PNCounter {
+        increments: GCounter::default(),
+        decrements: e.increments.clone(),
+    };
+    assert_eq!(g.value(), i64::MIN);
+    let mut h = e.clone();
+    h.increment("Alice");
+    assert_eq!(h, e);
+    assert_eq!(h.increments.inner.entry("Alice"), Some(&Max(u64::MAX)));
+
+    partially_verify_semilattice_laws([a.clone(), b.clone(), c.clone(), d.clone()]);
+    partially_verify_decomposition([a, b, c, d]);
+}
@@ -1,6 +1,6 @@
-use core::{cell, cmp, mem, ops};
+use core::{cell, mem, ops};
 
-use crate::Semilattice;
+use crate::{BoundedLattice, Semilattice};
 
 pub trait DeferredRestore {
     type Value;
@@ -95,11 +95,25 @@ where
 
 #[derive(Debug, Default)]
 pub struct Simple<S> {
+    // every value before the recent one
     stable: S,
+    // the values inserted last round
     recent: S,
+    // (potentially) new values, yet to be processed
     pending: S,
 }
 
+impl<S> Simple<S>
+where
+    S: BoundedLattice,
+{
+    /// Whether the stable value has reached the top element, after which no
+    /// insertion can change it and the iteration may stop early.
+    pub fn is_saturated(&self) -> bool {
+        self.stable.is_top()
+    }
+}
+
 impl<S> DeferredRestore for Simple<S>
 where
     S: Semilattice,
@@ -107,13 +121,13 @@ where
     type Value = S;
 
     fn for_each_stable(&self, mut func: impl FnMut(&Self::Value)) {
-        if self.stable > S::default() {
+        if !self.stable.is_bottom() {
             func(&self.stable)
         }
     }
 
     fn for_each_recent(&self, mut func: impl FnMut(&Self::Value)) {
-        if self.recent > S::default() {
+        if !self.recent.is_bottom() {
             func(&self.recent)
         }
     }
@@ -127,10 +141,7 @@ where
             .join_assign(mem::replace(&mut self.recent, mem::take(&mut self.pending)));
 
         // continue until stable >= recent
-        matches!(
-            self.stable.partial_cmp(&self.recent),
-            None | Some(cmp::Ordering::Less)
-        )
+        !self.recent.leq(&self.stable)
     }
 
     fn join<T, Y>(&mut self, other: &T, mut func: impl FnMut(&Self::Value, &T::Value) -> Y)
@@ -138,11 +149,11 @@ where
         T: DeferredRestore,
         Y: Into<Self::Value>,
     {
-        if self.recent > S::default() {
+        if !self.recent.is_bottom() {
             other.for_each_stable(|b| self.pending.join_assign(func(&self.recent, b).into()));
         }
 
-        match (self.stable > S::default(), self.recent > S::default()) {
+        match (!self.stable.is_bottom(), !self.recent.is_bottom()) {
             (true, true) => other.for_each_recent(|b| {
                 self.insert(func(&self.stable, b));
                 self.insert(func(&self.recent, b));
@@ -183,4 +194,10 @@ fn check() {
     assert_eq!(iteration.rounds, 46);
     assert_eq!(x.pending, Max::default());
     assert_eq!(interval.pending, Interval::default());
+    assert!(!interval.is_saturated());
+
+    interval.insert((10, 0));
+    interval.restore();
+    interval.restore();
+    assert!(interval.is_saturated());
 }
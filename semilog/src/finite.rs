@@ -0,0 +1,231 @@
+use core::cmp::Ordering;
+
+use crate::{ConflictMetadata, Flat, GuardedPair, Max, Min, Pair, Redactable, Semilattice};
+
+#[cfg(feature = "alloc")]
+use {crate::SetLattice, alloc::vec::Vec};
+
+/// A type with finitely many values, every one of which can be listed.
+pub trait Finite: Clone {
+    fn for_each_element(func: impl FnMut(Self));
+}
+
+/// A law which a `Semilattice` failed, with the elements which violate it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LawViolation<S> {
+    Associativity(S, S, S),
+    Commutativity(S, S),
+    Idempotence(S),
+    /// Bottom is not the least element, or not the identity of the join.
+    Bottom(S),
+    /// `partial_cmp` is not consistent with equality, or with itself when
+    /// its arguments are swapped.
+    PartialOrd(S, S),
+    /// The partial order is not consistent with the least upper bound.
+    Join(S, S),
+    Antisymmetry(S, S),
+    Transitivity(S, S, S),
+}
+
+/// Verify the semantics of a finite `Semilattice` over every element of its
+/// carrier set, returning the number of elements on success and the first
+/// violation otherwise. This takes time cubic in the number of elements.
+pub fn verify_finite_semilattice<S>() -> Result<usize, LawViolation<S>>
+where
+    S: Semilattice + Finite,
+{
+    let mut violation = None;
+    let mut elements = 0;
+
+    S::for_each_element(|a| {
+        elements += 1;
+        if violation.is_some() {
+            return;
+        }
+
+        let bottom = S::default();
+        if !bottom.leq(&a) || bottom.join(a.clone()) != a {
+            violation = Some(LawViolation::Bottom(a));
+            return;
+        }
+        if a.clone().join(a.clone()) != a {
+            violation = Some(LawViolation::Idempotence(a));
+            return;
+        }
+
+        S::for_each_element(|b| {
+            if violation.is_some() {
+                return;
+            }
+
+            let ab = a.clone().join(b.clone());
+            let ord = a.partial_cmp(&b);
+
+            violation = if ab != b.clone().join(a.clone()) {
+                Some(LawViolation::Commutativity(a.clone(), b.clone()))
+            } else if ord != b.partial_cmp(&a).map(Ordering::reverse)
+                || (a == b) != (ord == Some(Ordering::Equal))
+            {
+                Some(LawViolation::PartialOrd(a.clone(), b.clone()))
+            } else if (a <= b) != (ab == b) || !a.leq(&ab) || !b.leq(&ab) {
+                Some(LawViolation::Join(a.clone(), b.clone()))
+            } else if a <= b && b <= a && a != b {
+                Some(LawViolation::Antisymmetry(a.clone(), b.clone()))
+            } else {
+                None
+            };
+
+            S::for_each_element(|c| {
+                if violation.is_some() {
+                    return;
+                }
+
+                if ab.clone().join(c.clone()) != a.clone().join(b.clone().join(c.clone())) {
+                    violation = Some(LawViolation::Associativity(a.clone(), b.clone(), c));
+                } else if a <= b && b <= c && !a.leq(&c) {
+                    violation = Some(LawViolation::Transitivity(a.clone(), b.clone(), c));
+                }
+            });
+        });
+    });
+
+    match violation {
+        Some(violation) => Err(violation),
+        None => Ok(elements),
+    }
+}
+
+impl Finite for () {
+    fn for_each_element(mut func: impl FnMut(Self)) {
+        func(())
+    }
+}
+
+impl Finite for bool {
+    fn for_each_element(mut func: impl FnMut(Self)) {
+        func(false);
+        func(true);
+    }
+}
+
+impl Finite for u8 {
+    fn for_each_element(func: impl FnMut(Self)) {
+        (u8::MIN..=u8::MAX).for_each(func)
+    }
+}
+
+impl Finite for i8 {
+    fn for_each_element(func: impl FnMut(Self)) {
+        (i8::MIN..=i8::MAX).for_each(func)
+    }
+}
+
+impl<T> Finite for Max<T>
+where
+    T: Finite,
+{
+    fn for_each_element(mut func: impl FnMut(Self)) {
+        T::for_each_element(|t| func(Max(t)))
+    }
+}
+
+impl<T> Finite for Min<T>
+where
+    T: Finite,
+{
+    fn for_each_element(mut func: impl FnMut(Self)) {
+        T::for_each_element(|t| func(Min(t)))
+    }
+}
+
+impl<T, M> Finite for Flat<T, M>
+where
+    T: Finite + PartialEq,
+    M: ConflictMetadata<T> + Finite,
+{
+    fn for_each_element(mut func: impl FnMut(Self)) {
+        func(Flat::Bottom);
+        T::for_each_element(|t| func(Flat::Known(t)));
+        M::for_each_element(|m| func(Flat::conflict(m)));
+    }
+}
+
+impl<T, M> Finite for Redactable<T, M>
+where
+    T: Finite + PartialEq,
+    M: ConflictMetadata<T> + Finite,
+{
+    fn for_each_element(mut func: impl FnMut(Self)) {
+        Flat::for_each_element(|inner| func(Redactable { inner }))
+    }
+}
+
+impl<A, B> Finite for Pair<A, B>
+where
+    A: Finite,
+    B: Finite,
+{
+    fn for_each_element(mut func: impl FnMut(Self)) {
+        A::for_each_element(|a| B::for_each_element(|b| func(Pair(a.clone(), b))))
+    }
+}
+
+impl<G, V> Finite for GuardedPair<G, V>
+where
+    G: Finite,
+    V: Finite,
+{
+    fn for_each_element(mut func: impl FnMut(Self)) {
+        G::for_each_element(|guard| {
+            V::for_each_element(|value| {
+                func(GuardedPair {
+                    guard: guard.clone(),
+                    value,
+                })
+            })
+        })
+    }
+}
+
+/// Every subset of the domain of `T`, which must have fewer than 64 elements.
+#[cfg(feature = "alloc")]
+impl<T> Finite for SetLattice<T>
+where
+    T: Finite + Ord,
+{
+    fn for_each_element(mut func: impl FnMut(Self)) {
+        let mut domain = Vec::new();
+        T::for_each_element(|t| domain.push(t));
+        assert!(domain.len() < 64, "Domain is too large to enumerate");
+
+        for subset in 0..1u64 << domain.len() {
+            func(
+                domain
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| subset & (1 << i) != 0)
+                    .map(|(_, t)| t.clone())
+                    .collect(),
+            )
+        }
+    }
+}
+
+#[test]
+fn check_laws() {
+    assert_eq!(verify_finite_semilattice::<()>(), Ok(1));
+    assert_eq!(verify_finite_semilattice::<Redactable<bool>>(), Ok(4));
+    assert_eq!(verify_finite_semilattice::<Max<u8>>(), Ok(256));
+    assert_eq!(verify_finite_semilattice::<Min<i8>>(), Ok(256));
+    assert_eq!(
+        verify_finite_semilattice::<Pair<Redactable<bool>, Redactable<()>>>(),
+        Ok(12)
+    );
+    assert_eq!(
+        verify_finite_semilattice::<GuardedPair<bool, Redactable<bool>>>(),
+        Ok(8)
+    );
+
+    #[cfg(feature = "alloc")]
+    assert_eq!(verify_finite_semilattice::<SetLattice<bool>>(), Ok(4));
+}
@@ -0,0 +1,247 @@
+use core::cmp::Ordering;
+
+use crate::{BoundedLattice, Decompose, JoinRef, Semilattice};
+
+/// The metadata kept by the top element of a `Flat` lattice, such as who
+/// caused a conflict and why, or which values were seen.
+pub trait ConflictMetadata<T>: Semilattice {
+    /// The metadata recorded for a value which became part of a conflict.
+    fn conflicting(_value: &T) -> Self {
+        Self::default()
+    }
+}
+
+impl<T> ConflictMetadata<T> for () {}
+
+#[cfg(feature = "alloc")]
+impl<T> ConflictMetadata<T> for crate::SetLattice<T>
+where
+    T: Ord + Clone,
+{
+    fn conflicting(value: &T) -> Self {
+        Self::singleton(value.clone())
+    }
+}
+
+/// A flat lattice, as used for constant propagation: a value is unknown,
+/// known, or known to have conflicting values. The data is arbitrary, not a
+/// semilattice, so joining two different values is a conflict.
+///
+/// `None` stands for the default metadata of a conflict, so that conflicts
+/// without metadata are encoded compactly. Joins never produce
+/// `Some(M::default())`, and where one is built or decoded it is equal to
+/// `None`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
+pub enum Flat<T, M = ()> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    Bottom,
+    #[cfg_attr(feature = "minicbor", n(1))]
+    Known(#[cfg_attr(feature = "minicbor", n(0))] T),
+    #[cfg_attr(feature = "minicbor", n(2))]
+    Conflict(#[cfg_attr(feature = "minicbor", n(0))] Option<M>),
+}
+
+impl<T, M> Flat<T, M>
+where
+    T: PartialEq,
+    M: ConflictMetadata<T>,
+{
+    /// A conflict with the given metadata.
+    pub fn conflict(metadata: M) -> Self {
+        if metadata == M::default() {
+            Self::Conflict(None)
+        } else {
+            Self::Conflict(Some(metadata))
+        }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        match self {
+            Self::Known(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, Self::Conflict(_))
+    }
+
+    /// The metadata of a conflict, if it is not the default.
+    pub fn metadata(&self) -> Option<&M> {
+        match self {
+            Self::Conflict(metadata) => metadata.as_ref().filter(|m| **m != M::default()),
+            _ => None,
+        }
+    }
+
+    // The metadata this contributes to a conflict.
+    fn into_metadata(self) -> M {
+        match self {
+            Self::Bottom => M::default(),
+            Self::Known(t) => M::conflicting(&t),
+            Self::Conflict(metadata) => metadata.unwrap_or_default(),
+        }
+    }
+}
+
+// Deriving `Default` would needlessly require `T: Default`.
+#[allow(clippy::derivable_impls)]
+impl<T, M> Default for Flat<T, M> {
+    fn default() -> Self {
+        Self::Bottom
+    }
+}
+
+// Deriving `PartialEq` would tell `Conflict(None)` apart from
+// `Conflict(Some(M::default()))`, which is the same conflict.
+impl<T, M> PartialEq for Flat<T, M>
+where
+    T: PartialEq,
+    M: PartialEq + Default,
+{
+    fn eq(&self, other: &Self) -> bool {
+        use Flat::{Bottom, Conflict, Known};
+
+        match (self, other) {
+            (Bottom, Bottom) => true,
+            (Known(a), Known(b)) => a == b,
+            (Conflict(a), Conflict(b)) => {
+                let default = M::default();
+                or_default(a, &default) == or_default(b, &default)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<T, M> PartialOrd for Flat<T, M>
+where
+    T: PartialEq,
+    M: ConflictMetadata<T>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        use Flat::{Bottom, Conflict, Known};
+
+        let default = M::default();
+        let metadata = |m| or_default(m, &default);
+
+        match (self, other) {
+            (a, b) if a == b => Some(Ordering::Equal),
+            (Bottom, _) => Some(Ordering::Less),
+            (_, Bottom) => Some(Ordering::Greater),
+            (Known(_), Known(_)) => None,
+            (Known(t), Conflict(m)) => {
+                (&M::conflicting(t) <= metadata(m)).then_some(Ordering::Less)
+            }
+            (Conflict(m), Known(t)) => {
+                (&M::conflicting(t) <= metadata(m)).then_some(Ordering::Greater)
+            }
+            (Conflict(a), Conflict(b)) => metadata(a).partial_cmp(metadata(b)),
+        }
+    }
+}
+
+// `None` stands for the default metadata of a conflict.
+fn or_default<'a, M>(metadata: &'a Option<M>, default: &'a M) -> &'a M {
+    metadata.as_ref().unwrap_or(default)
+}
+
+impl<T, M> Semilattice for Flat<T, M>
+where
+    T: PartialEq,
+    M: ConflictMetadata<T>,
+{
+    fn join(self, other: Self) -> Self {
+        use Flat::{Bottom, Known};
+
+        match (self, other) {
+            (Bottom, b) => b,
+            (a, Bottom) => a,
+            (Known(a), Known(b)) if a == b => Known(a),
+            (a, b) => Self::conflict(a.into_metadata().join(b.into_metadata())),
+        }
+    }
+}
+
+impl<T, M> JoinRef for Flat<T, M>
+where
+    T: PartialEq + Clone,
+    M: ConflictMetadata<T> + Clone,
+{
+}
+
+impl<T, M> BoundedLattice for Flat<T, M>
+where
+    T: PartialEq,
+    M: ConflictMetadata<T> + BoundedLattice,
+{
+    fn top() -> Self {
+        Self::conflict(M::top())
+    }
+
+    fn is_top(&self) -> bool {
+        match self {
+            Self::Conflict(None) => M::default().is_top(),
+            Self::Conflict(Some(metadata)) => metadata.is_top(),
+            _ => false,
+        }
+    }
+}
+
+impl<T, M> Decompose for Flat<T, M>
+where
+    T: PartialEq,
+    M: ConflictMetadata<T>,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if !matches!(self, Self::Bottom) {
+            func(self)
+        }
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_decomposition, partially_verify_semilattice_laws};
+
+    use Flat::{Bottom, Conflict, Known};
+
+    let samples: [Flat<_>; 4] = [Bottom, Known(1), Known(2), Conflict(None)];
+    partially_verify_semilattice_laws(samples.clone());
+    partially_verify_decomposition(samples);
+
+    assert_eq!(Known(1).join(Known(1)), Flat::<_, ()>::Known(1));
+    assert_eq!(Known(1).join(Known(2)), Flat::<_, ()>::top());
+    assert!(Flat::<u8, ()>::Conflict(None).is_top());
+    assert_eq!(Flat::<u8, ()>::Conflict(Some(())), Conflict(None));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_metadata() {
+    use crate::{partially_verify_semilattice_laws, SetLattice};
+
+    use Flat::{Bottom, Conflict, Known};
+
+    // conflicting values are recorded.
+    let conflict = Known(1).join(Known(2));
+    assert_eq!(conflict.metadata(), Some(&SetLattice::from_iter([1, 2])));
+    assert!(Known(2) < conflict && Known(3).partial_cmp(&conflict).is_none());
+    assert_eq!(
+        conflict.clone().join(Known(3)).metadata(),
+        Some(&SetLattice::from_iter([1, 2, 3]))
+    );
+
+    partially_verify_semilattice_laws([
+        Bottom,
+        Known(1),
+        Known(2),
+        Known(3),
+        conflict,
+        Flat::conflict(SetLattice::from_iter([3])),
+        Conflict(None),
+        Conflict(Some(SetLattice::default())),
+    ]);
+}
@@ -1,8 +1,12 @@
 use core::cmp::{Ordering, PartialOrd};
 
-use crate::Semilattice;
+use crate::{Decompose, JoinRef, Semilattice};
 
 /// A pair of semilattices, where the former acts as to version the latter.
+///
+/// The guard must be totally ordered, hence the `Ord` bound: there is no
+/// associative join of values under incomparable guards, which neither
+/// replaces the other.
 #[derive(Default, Debug, PartialEq, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
@@ -15,7 +19,7 @@ pub struct GuardedPair<G, V> {
 
 impl<G, V> PartialOrd for GuardedPair<G, V>
 where
-    G: Semilattice,
+    G: Semilattice + Ord,
     V: Semilattice,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -28,30 +32,76 @@ where
 
 impl<G, V> Semilattice for GuardedPair<G, V>
 where
-    G: Semilattice,
+    G: Semilattice + Ord,
     V: Semilattice,
 {
     fn join(self, other: Self) -> Self {
         match self.guard.partial_cmp(&other.guard) {
             Some(Ordering::Greater) => self,
             Some(Ordering::Less) => other,
-            Some(Ordering::Equal) => Self {
+            // the guards are totally ordered, so they are equal.
+            _ => Self {
                 value: self.value.join(other.value),
                 ..self
             },
-            None => Self {
-                guard: self.guard.join(other.guard),
-                value: self.value.join(other.value),
+        }
+    }
+
+    fn delta(self, since: &Self) -> Self {
+        match self.guard.partial_cmp(&since.guard) {
+            Some(Ordering::Less) => Self::default(),
+            // Only values under the same guard are merged; anything else
+            // replaces or is joined with `since` wholesale.
+            Some(Ordering::Equal) if self.value <= since.value => Self::default(),
+            Some(Ordering::Equal) => Self {
+                value: self.value.delta(&since.value),
+                ..self
             },
+            _ => self,
+        }
+    }
+}
+
+impl<G, V> JoinRef for GuardedPair<G, V>
+where
+    G: Semilattice + Ord + Clone,
+    V: Semilattice + Clone,
+{
+}
+
+impl<G, V> Decompose for GuardedPair<G, V>
+where
+    G: Semilattice + Ord + Clone,
+    V: Decompose,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        // The guard is kept whole, as parts under different guards would
+        // replace rather than join each other.
+        let mut empty = true;
+        let guard = self.guard;
+
+        self.value.decompose(|value| {
+            empty = false;
+            func(Self {
+                guard: guard.clone(),
+                value,
+            })
+        });
+
+        if empty && guard > G::default() {
+            func(Self {
+                guard,
+                value: V::default(),
+            })
         }
     }
 }
 
 #[test]
 fn check_laws() {
-    use crate::{partially_verify_semilattice_laws, Max, Min};
+    use crate::{partially_verify_decomposition, partially_verify_semilattice_laws, Max, Min};
 
-    partially_verify_semilattice_laws([
+    let samples = [
         GuardedPair {
             guard: Min(0),
             value: Max(0),
@@ -68,5 +118,8 @@ fn check_laws() {
             guard: Min(1),
             value: Max(0),
         },
-    ]);
+    ];
+
+    partially_verify_semilattice_laws(samples);
+    partially_verify_decomposition(samples);
 }
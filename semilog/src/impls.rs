@@ -0,0 +1,595 @@
+//! Implementations for types from `core`, `alloc` and `std`. Their order is
+//! the one given by their `PartialOrd`, so tuples and arrays are ordered, and
+//! joined, lexicographically; use `Pair` for the product order.
+//!
+//! The `PartialOrd` of the standard collections is lexicographic over their
+//! elements, or missing for the hashed ones, rather than the subset order.
+//! Instead, they implement `JoinCollection`, and can be converted to and from
+//! `SetLattice` and `MapLattice`.
+
+use core::cmp::Ordering;
+
+use crate::{BoundedLattice, Decompose, JoinRef, Lattice, Semilattice};
+
+/// `false` is the bottom element, and the join is a logical or.
+impl Semilattice for bool {
+    fn join(self, other: Self) -> Self {
+        self | other
+    }
+}
+
+impl JoinRef for bool {}
+
+impl Lattice for bool {
+    fn meet(self, other: Self) -> Self {
+        self & other
+    }
+}
+
+impl BoundedLattice for bool {
+    fn top() -> Self {
+        true
+    }
+}
+
+impl Decompose for bool {
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if self {
+            func(self)
+        }
+    }
+}
+
+/// `None` is the bottom element, below every `Some`.
+impl<T> Semilattice for Option<T>
+where
+    T: Semilattice,
+{
+    fn join(self, other: Self) -> Self {
+        match (self, other) {
+            (Some(a), Some(b)) => Some(a.join(b)),
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.join_assign_changed(b),
+            (_, None) => false,
+            (a, b) => {
+                *a = b;
+                true
+            }
+        }
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.leq(b),
+            (a, _) => a.is_none(),
+        }
+    }
+
+    fn is_bottom(&self) -> bool {
+        self.is_none()
+    }
+}
+
+impl<T> JoinRef for Option<T>
+where
+    T: JoinRef,
+{
+    fn join_assign_ref(&mut self, other: &Self) {
+        match (self, other) {
+            (Some(a), Some(b)) => a.join_assign_ref(b),
+            (_, None) => (),
+            (a, b) => *a = b.clone(),
+        }
+    }
+}
+
+impl<T> Lattice for Option<T>
+where
+    T: Lattice,
+{
+    fn meet(self, other: Self) -> Self {
+        match (self, other) {
+            (Some(a), Some(b)) => Some(a.meet(b)),
+            _ => None,
+        }
+    }
+}
+
+impl<T> BoundedLattice for Option<T>
+where
+    T: BoundedLattice,
+{
+    fn top() -> Self {
+        Some(T::top())
+    }
+}
+
+impl<T> Decompose for Option<T>
+where
+    T: Decompose,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if let Some(t) = self {
+            let mut empty = true;
+
+            t.decompose(|part| {
+                empty = false;
+                func(Some(part))
+            });
+
+            // `Some` of bottom is still above `None`.
+            if empty {
+                func(Some(T::default()))
+            }
+        }
+    }
+}
+
+macro_rules! tuple_impls {
+    ($(($($name:ident $i:tt),+))+) => {$(
+        /// Ordered lexicographically. If the first field which differs is
+        /// incomparable, the join is the join of that field followed by bottom.
+        impl<$($name),+> Semilattice for ($($name,)+)
+        where
+            $($name: Semilattice,)+
+        {
+            // the flag is not read after the last field.
+            #[allow(unused_assignments)]
+            fn join(self, other: Self) -> Self {
+                match self.partial_cmp(&other) {
+                    Some(Ordering::Greater | Ordering::Equal) => self,
+                    Some(Ordering::Less) => other,
+                    None => {
+                        let mut incomparable = false;
+
+                        ($(
+                            if incomparable {
+                                $name::default()
+                            } else if self.$i.partial_cmp(&other.$i).is_none() {
+                                incomparable = true;
+                                self.$i.join(other.$i)
+                            } else {
+                                self.$i
+                            },
+                        )+)
+                    }
+                }
+            }
+        }
+
+        impl<$($name),+> JoinRef for ($($name,)+)
+        where
+            $($name: Semilattice + Clone,)+
+        {
+        }
+    )+};
+}
+
+tuple_impls! {
+    (A 0)
+    (A 0, B 1)
+    (A 0, B 1, C 2)
+    (A 0, B 1, C 2, D 3)
+    (A 0, B 1, C 2, D 3, E 4)
+    (A 0, B 1, C 2, D 3, E 4, F 5)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11)
+}
+
+// `Default` is only implemented for arrays of up to 32 elements.
+macro_rules! array_impls {
+    ($($n:literal)+) => {$(
+        /// Ordered lexicographically, like tuples.
+        impl<T> Semilattice for [T; $n]
+        where
+            T: Semilattice,
+        {
+            fn join(self, other: Self) -> Self {
+                match self.partial_cmp(&other) {
+                    Some(Ordering::Greater | Ordering::Equal) => self,
+                    Some(Ordering::Less) => other,
+                    None => {
+                        let mut incomparable = false;
+                        let mut other = other.into_iter();
+
+                        self.map(|a| {
+                            let b = other.next().expect("Arrays have the same length");
+                            if incomparable {
+                                T::default()
+                            } else if a.partial_cmp(&b).is_none() {
+                                incomparable = true;
+                                a.join(b)
+                            } else {
+                                a
+                            }
+                        })
+                    }
+                }
+            }
+        }
+
+        impl<T> JoinRef for [T; $n]
+        where
+            T: Semilattice + Clone,
+        {
+        }
+    )+};
+}
+
+array_impls! {
+    1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+    17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+}
+
+/// The join of a standard collection: the union of sets, and the union of
+/// maps with the values of shared keys joined. Bottom values are treated as
+/// missing keys, as in `MapLattice`. This is the join of `SetLattice` and
+/// `MapLattice`, for collections which can't implement `Semilattice` because
+/// of their `PartialOrd`.
+pub trait JoinCollection: Default {
+    /// Join `other` into `self`, returning whether `self` was inflated.
+    fn join_assign_changed(&mut self, other: Self) -> bool;
+
+    /// Whether `self` is included in `other`, the order of the join.
+    fn leq(&self, other: &Self) -> bool;
+
+    fn join(mut self, other: Self) -> Self {
+        self.join_assign_changed(other);
+        self
+    }
+
+    fn join_assign(&mut self, other: Self) {
+        self.join_assign_changed(other);
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod alloc_impls {
+    use alloc::{
+        boxed::Box,
+        collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    };
+
+    use crate::{Decompose, JoinCollection, JoinRef, Lattice, MapLattice, Semilattice, SetLattice};
+
+    impl<T> Semilattice for Box<T>
+    where
+        T: Semilattice,
+    {
+        fn join(self, other: Self) -> Self {
+            Box::new((*self).join(*other))
+        }
+
+        fn join_assign_changed(&mut self, other: Self) -> bool {
+            (**self).join_assign_changed(*other)
+        }
+
+        fn leq(&self, other: &Self) -> bool {
+            (**self).leq(&**other)
+        }
+
+        fn is_bottom(&self) -> bool {
+            (**self).is_bottom()
+        }
+
+        fn delta(self, since: &Self) -> Self {
+            Box::new((*self).delta(&**since))
+        }
+    }
+
+    impl<T> JoinRef for Box<T>
+    where
+        T: JoinRef,
+    {
+        fn join_assign_ref(&mut self, other: &Self) {
+            (**self).join_assign_ref(&**other)
+        }
+    }
+
+    impl<T> Lattice for Box<T>
+    where
+        T: Lattice,
+    {
+        fn meet(self, other: Self) -> Self {
+            Box::new((*self).meet(*other))
+        }
+    }
+
+    impl<T> Decompose for Box<T>
+    where
+        T: Decompose,
+    {
+        fn decompose(self, mut func: impl FnMut(Self)) {
+            (*self).decompose(|part| func(Box::new(part)))
+        }
+    }
+
+    impl<V> JoinCollection for BTreeSet<V>
+    where
+        V: Ord,
+    {
+        fn join_assign_changed(&mut self, other: Self) -> bool {
+            other
+                .into_iter()
+                .fold(false, |changed, v| self.insert(v) | changed)
+        }
+
+        fn leq(&self, other: &Self) -> bool {
+            self.is_subset(other)
+        }
+    }
+
+    impl<K, V> JoinCollection for BTreeMap<K, V>
+    where
+        K: Ord,
+        V: Semilattice,
+    {
+        fn join_assign_changed(&mut self, other: Self) -> bool {
+            let mut changed = false;
+
+            for (k, v) in other {
+                match self.entry(k) {
+                    Entry::Occupied(mut entry) => changed |= entry.get_mut().join_assign_changed(v),
+                    Entry::Vacant(entry) if !v.is_bottom() => {
+                        entry.insert(v);
+                        changed = true;
+                    }
+                    Entry::Vacant(_) => (),
+                }
+            }
+
+            changed
+        }
+
+        fn leq(&self, other: &Self) -> bool {
+            self.iter()
+                .all(|(k, v)| other.get(k).map_or_else(|| v.is_bottom(), |w| v.leq(w)))
+        }
+    }
+
+    impl<V> From<BTreeSet<V>> for SetLattice<V>
+    where
+        V: Ord,
+    {
+        fn from(set: BTreeSet<V>) -> Self {
+            set.into_iter().collect()
+        }
+    }
+
+    impl<V> From<SetLattice<V>> for BTreeSet<V>
+    where
+        V: Ord,
+    {
+        fn from(set: SetLattice<V>) -> Self {
+            set.inner.into_iter().collect()
+        }
+    }
+
+    impl<K, V> From<BTreeMap<K, V>> for MapLattice<K, V>
+    where
+        K: Ord,
+        V: Semilattice,
+    {
+        fn from(map: BTreeMap<K, V>) -> Self {
+            map.into_iter().collect()
+        }
+    }
+
+    impl<K, V> From<MapLattice<K, V>> for BTreeMap<K, V>
+    where
+        K: Ord,
+    {
+        fn from(map: MapLattice<K, V>) -> Self {
+            map.inner.into_iter().collect()
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_impls {
+    use core::hash::{BuildHasher, Hash};
+    use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+    use crate::{JoinCollection, MapLattice, Semilattice, SetLattice};
+
+    impl<V, H> JoinCollection for HashSet<V, H>
+    where
+        V: Eq + Hash,
+        H: BuildHasher + Default,
+    {
+        fn join_assign_changed(&mut self, other: Self) -> bool {
+            other
+                .into_iter()
+                .fold(false, |changed, v| self.insert(v) | changed)
+        }
+
+        fn leq(&self, other: &Self) -> bool {
+            self.is_subset(other)
+        }
+    }
+
+    impl<K, V, H> JoinCollection for HashMap<K, V, H>
+    where
+        K: Eq + Hash,
+        V: Semilattice,
+        H: BuildHasher + Default,
+    {
+        fn join_assign_changed(&mut self, other: Self) -> bool {
+            let mut changed = false;
+
+            for (k, v) in other {
+                match self.entry(k) {
+                    Entry::Occupied(mut entry) => changed |= entry.get_mut().join_assign_changed(v),
+                    Entry::Vacant(entry) if !v.is_bottom() => {
+                        entry.insert(v);
+                        changed = true;
+                    }
+                    Entry::Vacant(_) => (),
+                }
+            }
+
+            changed
+        }
+
+        fn leq(&self, other: &Self) -> bool {
+            self.iter()
+                .all(|(k, v)| other.get(k).map_or_else(|| v.is_bottom(), |w| v.leq(w)))
+        }
+    }
+
+    impl<V, H> From<HashSet<V, H>> for SetLattice<V>
+    where
+        V: Ord,
+    {
+        fn from(set: HashSet<V, H>) -> Self {
+            set.into_iter().collect()
+        }
+    }
+
+    impl<V, H> From<SetLattice<V>> for HashSet<V, H>
+    where
+        V: Eq + Hash,
+        H: BuildHasher + Default,
+    {
+        fn from(set: SetLattice<V>) -> Self {
+            set.inner.into_iter().collect()
+        }
+    }
+
+    impl<K, V, H> From<HashMap<K, V, H>> for MapLattice<K, V>
+    where
+        K: Ord,
+        V: Semilattice,
+    {
+        fn from(map: HashMap<K, V, H>) -> Self {
+            map.into_iter().collect()
+        }
+    }
+
+    impl<K, V, H> From<MapLattice<K, V>> for HashMap<K, V, H>
+    where
+        K: Eq + Hash,
+        H: BuildHasher + Default,
+    {
+        fn from(map: MapLattice<K, V>) -> Self {
+            map.inner.into_iter().collect()
+        }
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{
+        partially_verify_decomposition, partially_verify_distributive_lattice_laws,
+        partially_verify_semilattice_laws, Flat, Max,
+    };
+
+    use Flat::{Bottom, Conflict, Known};
+
+    partially_verify_distributive_lattice_laws([false, true]);
+    partially_verify_distributive_lattice_laws([None, Some(Max(0u8)), Some(Max(3))]);
+    partially_verify_decomposition([None, Some(Max(0u8)), Some(Max(3))]);
+
+    // the first incomparable field is joined, and the rest reset.
+    assert_eq!(
+        (Known(1), Max(5u8), true).join((Known(2), Max(3), false)),
+        (Conflict::<_, ()>(None), Max(0), false)
+    );
+    assert_eq!(
+        (Known(1), Max(5u8), true).join((Known(1), Max(3), false)),
+        (Known::<_, ()>(1), Max(5), true)
+    );
+    partially_verify_semilattice_laws([
+        (Bottom, Max(0u8)),
+        (Known(1), Max(5)),
+        (Known(1), Max(3)),
+        (Known(2), Max(7)),
+        (Conflict::<_, ()>(None), Max(1)),
+    ]);
+    partially_verify_semilattice_laws([
+        [Known(1), Known(2), Known(3)],
+        [Known(1), Known(4), Known(3)],
+        [Known(2), Bottom, Known(3)],
+        [Bottom, Bottom, Flat::<_, ()>::Bottom],
+    ]);
+
+    #[cfg(feature = "alloc")]
+    {
+        use alloc::{
+            boxed::Box,
+            collections::{BTreeMap, BTreeSet},
+        };
+
+        use crate::{MapLattice, SetLattice};
+
+        partially_verify_semilattice_laws([Box::new(Max(1u8)), Box::new(Max(2))]);
+
+        // bottom values are dropped, as they are the same as missing keys.
+        let map = MapLattice::from(BTreeMap::from([(1, Max(0u8)), (2, Max(1))]));
+        assert_eq!(map.inner, [(2, Max(1))]);
+        assert_eq!(BTreeMap::from(map), BTreeMap::from([(2, Max(1))]));
+
+        let set = SetLattice::from(BTreeSet::from([3, 1, 2]));
+        assert_eq!(set, SetLattice::from_iter([1, 2, 3]));
+        assert_eq!(BTreeSet::from(set), BTreeSet::from([1, 2, 3]));
+
+        // the join agrees with the one of `SetLattice`.
+        let sets = [
+            BTreeSet::new(),
+            BTreeSet::from([1]),
+            BTreeSet::from([1, 2]),
+            BTreeSet::from([3]),
+        ];
+        for a in &sets {
+            for b in &sets {
+                let (la, lb) = (SetLattice::from(a.clone()), SetLattice::from(b.clone()));
+                assert_eq!(a.leq(b), la <= lb);
+                let mut joined = a.clone();
+                assert_eq!(joined.join_assign_changed(b.clone()), !b.leq(a));
+                assert_eq!(SetLattice::from(joined), la.join(lb));
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    {
+        use std::collections::HashMap;
+
+        use crate::MapLattice;
+
+        let map = MapLattice::from(HashMap::from([(2, Max(1u8)), (1, Max(2))]));
+        assert_eq!(map, MapLattice::from_iter([(1, Max(2)), (2, Max(1))]));
+        assert_eq!(
+            HashMap::from(map),
+            HashMap::from([(1, Max(2)), (2, Max(1))])
+        );
+
+        // the join agrees with the one of `MapLattice`, with bottom values
+        // treated as missing.
+        let maps: [HashMap<u8, Max<u8>>; 4] = [
+            HashMap::new(),
+            HashMap::from([(1, Max(0))]),
+            HashMap::from([(1, Max(2)), (2, Max(1))]),
+            HashMap::from([(1, Max(1)), (3, Max(4))]),
+        ];
+        for a in &maps {
+            for b in &maps {
+                let (la, lb) = (MapLattice::from(a.clone()), MapLattice::from(b.clone()));
+                assert_eq!(a.leq(b), la <= lb);
+                let mut joined = a.clone();
+                assert_eq!(joined.join_assign_changed(b.clone()), !b.leq(a));
+                assert_eq!(MapLattice::from(joined), la.join(lb));
+            }
+        }
+    }
+}
@@ -1,40 +1,92 @@
+//! Semilattices, and a Datalog engine which joins them.
+//!
+//! The `PartialOrd` of a `Semilattice` is its lattice order, so the
+//! implementations for core types follow the `PartialOrd` they already have.
+//! Tuples and arrays are ordered lexicographically rather than as a product:
+//! the join of two incomparable values joins the first field which is
+//! incomparable, and resets every later field to bottom. Use `Pair` or a
+//! derived struct for the product order. The standard collections are ordered
+//! lexicographically too, so they implement `JoinCollection` instead.
+
 #![no_std]
 #![forbid(unsafe_code)]
-#![cfg_attr(feature = "alloc", feature(slice_partition_dedup))]
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 use core::{cmp, fmt, mem};
 
-pub use semilog_macros::Semilattice;
+pub use semilog_macros::{BoundedLattice, Decompose, Finite, JoinRef, Lattice, Semilattice};
 
+mod array;
+mod bitset;
 mod datalog;
+mod finite;
+mod flat;
 mod guarded_pair;
+mod impls;
+mod lww;
 mod ord;
 mod pair;
 mod redactable;
+mod toggle;
+
+#[cfg(feature = "proptest")]
+mod arbitrary;
 
+#[cfg(feature = "alloc")]
+mod counter;
 #[cfg(feature = "alloc")]
 mod map;
 #[cfg(feature = "alloc")]
+mod mv_register;
+#[cfg(feature = "alloc")]
+mod or_map;
+#[cfg(feature = "alloc")]
+mod sequence;
+#[cfg(feature = "alloc")]
 mod set;
 #[cfg(feature = "alloc")]
+mod tombstone_map;
+#[cfg(feature = "alloc")]
+mod two_phase_set;
+#[cfg(feature = "alloc")]
 mod vec;
+#[cfg(feature = "alloc")]
+mod version_vector;
 
 pub use {
+    array::{ArrayMapLattice, ArraySetLattice, ArrayVecLattice, CapacityError},
+    bitset::{BitSetLattice, Words},
     datalog::{DeferredRestore, Iteration, Simple},
+    finite::{verify_finite_semilattice, Finite, LawViolation},
+    flat::{ConflictMetadata, Flat},
     guarded_pair::GuardedPair,
-    ord::{Interval, Max, Min},
+    impls::JoinCollection,
+    lww::{Hlc, Lww},
+    ord::{Interval, Max, Maximum, Min, Minimum, TotalFloat},
     pair::Pair,
     redactable::Redactable,
+    toggle::{CausalLength, Toggle, ToggleState},
 };
 
+#[cfg(feature = "proptest")]
+pub use arbitrary::check_laws;
+
 #[cfg(feature = "alloc")]
 pub use {
-    map::{Map, MapLattice},
+    counter::{GCounter, PNCounter},
+    map::{JoinReport, Map, MapLattice},
+    mv_register::MvRegister,
+    or_map::{OrMap, OrSet},
+    sequence::{Position, Sequence},
     set::{Set, SetLattice},
+    tombstone_map::TombstoneMap,
+    two_phase_set::TwoPhaseSet,
     vec::VecLattice,
+    version_vector::{Dot, VersionVector},
 };
 
 /// A bounded join-semilattice whose `PartialOrd` obeys the lattice semantics
@@ -45,12 +97,98 @@ pub trait Semilattice: Default + PartialOrd {
     fn join_assign(&mut self, other: Self) {
         *self = mem::take(self).join(other);
     }
+
+    /// Join `other` into `self`, returning whether `self` was inflated.
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        if other.leq(self) {
+            false
+        } else {
+            self.join_assign(other);
+            true
+        }
+    }
+
+    /// Whether `self <= other`. The default implementation computes the whole
+    /// `partial_cmp`; collections should stop at the first entry of `self`
+    /// which is not below `other`.
+    fn leq(&self, other: &Self) -> bool {
+        self <= other
+    }
+
+    /// Whether `self` is the bottom element.
+    fn is_bottom(&self) -> bool {
+        self.leq(&Self::default())
+    }
+
+    /// The part of `self` which is not already known by `since`, such that
+    /// `since.join(self.delta(&since)) == since.join(self)`. The default
+    /// implementation is all or nothing; collections should only return the
+    /// entries that changed.
+    fn delta(self, since: &Self) -> Self {
+        if self.leq(since) {
+            Self::default()
+        } else {
+            self
+        }
+    }
 }
 
 impl Semilattice for () {
     fn join(self, _: Self) -> Self {}
 }
 
+/// A semilattice which can be joined from a borrowed value, without taking
+/// ownership of it. The default implementation clones all of `other` unless
+/// it is already known. Collections clone only the entries which they insert
+/// or change, and derived structs join field by field.
+pub trait JoinRef: Semilattice + Clone {
+    fn join_assign_ref(&mut self, other: &Self) {
+        if !other.leq(self) {
+            self.join_assign(other.clone());
+        }
+    }
+}
+
+impl JoinRef for () {}
+
+/// A semilattice whose values can be split into join-irreducible parts, such
+/// as single map entries or set elements. Folding the parts must give back
+/// the original value, and the bottom element has no parts.
+pub trait Decompose: Semilattice {
+    fn decompose(self, func: impl FnMut(Self));
+}
+
+impl Decompose for () {
+    fn decompose(self, _: impl FnMut(Self)) {}
+}
+
+/// A semilattice with a greatest element. Once a value reaches the top, no
+/// join can change it any further.
+pub trait BoundedLattice: Semilattice {
+    fn top() -> Self;
+
+    fn is_top(&self) -> bool {
+        *self >= Self::top()
+    }
+}
+
+impl BoundedLattice for () {
+    fn top() -> Self {}
+}
+
+/// A semilattice which also has a greatest lower bound for any two elements.
+pub trait Lattice: Semilattice {
+    fn meet(self, other: Self) -> Self;
+
+    fn meet_assign(&mut self, other: Self) {
+        *self = mem::take(self).meet(other);
+    }
+}
+
+impl Lattice for () {
+    fn meet(self, _: Self) -> Self {}
+}
+
 /// Reduce an iterator of semilattice values to its least upper bound.
 pub fn fold<S>(i: impl IntoIterator<Item = S>) -> S
 where
@@ -61,14 +199,15 @@ where
 
 /// Partially verify the semantics of a `Semilattice`. For all provided samples
 /// of the structure: the ACI properties must hold, the partial order must be
-/// consistent with the least upper bound, and the bottom element must be the
-/// least element.
+/// consistent with the least upper bound, the bottom element must be the
+/// least element, and every delta must recover the value it was taken from.
 ///
 /// ```lean
 /// ∀ a b c ∈ S,
 ///   (a + b) + c = a + (b + c)
 ///   ∧ a + b = b + a
 ///   ∧ a + a = a
+///   ∧ b + (a - b) = b + a
 /// ```
 pub fn partially_verify_semilattice_laws<S: Semilattice + fmt::Debug + Clone>(
     samples: impl IntoIterator<Item = S> + Clone,
@@ -83,11 +222,16 @@ pub fn partially_verify_semilattice_laws<S: Semilattice + fmt::Debug + Clone>(
             a,
             bottom.partial_cmp(&a)
         );
+        assert_eq!(a.is_bottom(), a <= bottom, "is_bottom: {:?}", a);
 
         // ACI properties & partial order consistency
         for b in samples.clone() {
-            // associative
             let ab = fold([a.clone(), b.clone()]);
+
+            // the shortcut agrees with the partial order
+            assert_eq!(a.leq(&b), a <= b, "leq: {:?}, {:?}", a, b);
+
+            // associative
             for c in samples.clone() {
                 assert_eq!(
                     fold([ab.clone(), c.clone()]),
@@ -97,6 +241,11 @@ pub fn partially_verify_semilattice_laws<S: Semilattice + fmt::Debug + Clone>(
             // commutative
             assert_eq!(&ab, &fold([b.clone(), a.clone()]));
 
+            // changes are reported exactly when the join inflates
+            let mut changed = a.clone();
+            assert_eq!(changed.join_assign_changed(b.clone()), ab != a);
+            assert_eq!(&changed, &ab);
+
             // The least upper bound is consistent with the partial order
             match a.partial_cmp(&b) {
                 Some(cmp::Ordering::Greater | cmp::Ordering::Equal) => assert_eq!(&ab, &a),
@@ -121,6 +270,150 @@ pub fn partially_verify_semilattice_laws<S: Semilattice + fmt::Debug + Clone>(
         }
         // idempotent
         assert_eq!(&a, &fold([a.clone(), a.clone()]));
+
+        // The delta is at most the original value, and recovers it when joined.
+        for b in samples.clone() {
+            let delta = a.clone().delta(&b);
+            assert!(
+                delta <= a,
+                "Expected delta {:?} <= {:?}; {:?}",
+                delta,
+                a,
+                delta.partial_cmp(&a)
+            );
+            if a <= b {
+                assert_eq!(&delta, &bottom);
+            }
+            assert_eq!(fold([b.clone(), delta]), fold([b, a.clone()]));
+        }
+    }
+}
+
+/// Partially verify the semantics of `JoinRef`. For all provided samples of
+/// the structure, a borrowed join must agree with the join.
+pub fn partially_verify_join_ref<S: JoinRef + fmt::Debug>(
+    samples: impl IntoIterator<Item = S> + Clone,
+) {
+    for a in samples.clone() {
+        for b in samples.clone() {
+            let mut ab = a.clone();
+            ab.join_assign_ref(&b);
+            assert_eq!(ab, a.clone().join(b), "join_assign_ref: {:?}", a);
+        }
+    }
+}
+
+/// Partially verify the semantics of a `Lattice`. In addition to the
+/// semilattice laws, for all provided samples: the meet must be ACI, the two
+/// operations must absorb each other, and the partial order must be consistent
+/// with the greatest lower bound.
+///
+/// ```lean
+/// ∀ a b c ∈ S,
+///   (a * b) * c = a * (b * c)
+///   ∧ a * b = b * a
+///   ∧ a * a = a
+///   ∧ a + (a * b) = a
+///   ∧ a * (a + b) = a
+/// ```
+pub fn partially_verify_lattice_laws<S: Lattice + fmt::Debug + Clone>(
+    samples: impl IntoIterator<Item = S> + Clone,
+) {
+    partially_verify_semilattice_laws(samples.clone());
+
+    for a in samples.clone() {
+        for b in samples.clone() {
+            let ab = a.clone().meet(b.clone());
+
+            // associative
+            for c in samples.clone() {
+                assert_eq!(
+                    ab.clone().meet(c.clone()),
+                    a.clone().meet(b.clone().meet(c))
+                )
+            }
+            // commutative
+            assert_eq!(&ab, &b.clone().meet(a.clone()));
+
+            // absorption
+            assert_eq!(&a, &a.clone().join(ab.clone()));
+            assert_eq!(&a, &a.clone().meet(a.clone().join(b.clone())));
+
+            // The greatest lower bound is consistent with the partial order
+            match a.partial_cmp(&b) {
+                Some(cmp::Ordering::Less | cmp::Ordering::Equal) => assert_eq!(&ab, &a),
+                Some(cmp::Ordering::Greater) => assert_eq!(&ab, &b),
+                None => {
+                    assert!(
+                        ab < a,
+                        "Expected {:?} < {:?}; {:?}",
+                        ab,
+                        a,
+                        ab.partial_cmp(&a)
+                    );
+                    assert!(
+                        ab < b,
+                        "Expected {:?} < {:?}; {:?}",
+                        ab,
+                        b,
+                        ab.partial_cmp(&b)
+                    );
+                }
+            }
+        }
+        // idempotent
+        assert_eq!(&a, &a.clone().meet(a.clone()));
+    }
+}
+
+/// Partially verify the semantics of a distributive `Lattice`. In addition to
+/// the lattice laws, the meet must distribute over the join.
+///
+/// ```lean
+/// ∀ a b c ∈ S,
+///   a * (b + c) = (a * b) + (a * c)
+/// ```
+pub fn partially_verify_distributive_lattice_laws<S: Lattice + fmt::Debug + Clone>(
+    samples: impl IntoIterator<Item = S> + Clone,
+) {
+    partially_verify_lattice_laws(samples.clone());
+
+    for a in samples.clone() {
+        for b in samples.clone() {
+            for c in samples.clone() {
+                assert_eq!(
+                    a.clone().meet(b.clone().join(c.clone())),
+                    fold([a.clone().meet(b.clone()), a.clone().meet(c)])
+                )
+            }
+        }
+    }
+}
+
+/// Partially verify the semantics of `Decompose`. For all provided samples of
+/// the structure: every part must be above bottom and below the sample, and
+/// the parts must fold back into the sample.
+pub fn partially_verify_decomposition<S: Decompose + fmt::Debug + Clone>(
+    samples: impl IntoIterator<Item = S>,
+) {
+    let bottom = S::default();
+
+    for a in samples {
+        let mut folded = S::default();
+
+        a.clone().decompose(|part| {
+            assert!(part > bottom, "Part {:?} of {:?} is bottom", part, a);
+            assert!(
+                part <= a,
+                "Expected part {:?} <= {:?}; {:?}",
+                part,
+                a,
+                part.partial_cmp(&a)
+            );
+            folded.join_assign(part);
+        });
+
+        assert_eq!(&folded, &a);
     }
 }
 
@@ -0,0 +1,216 @@
+use core::cmp::Ordering;
+
+use crate::{Decompose, JoinRef, Semilattice};
+
+/// A hybrid logical clock. The wall-clock time is kept close to the local
+/// clock, and the logical counter orders events within the same tick. As a
+/// semilattice, the join is the later of two timestamps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
+pub struct Hlc {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub wall: u64,
+    #[cfg_attr(feature = "minicbor", n(1))]
+    pub logical: u32,
+}
+
+impl Hlc {
+    pub fn new(wall: u64) -> Self {
+        Self { wall, logical: 0 }
+    }
+
+    /// Advance the clock for a local event, given the current time of the
+    /// local wall-clock, and return the new timestamp.
+    pub fn tick(&mut self, now: u64) -> Self {
+        *self = if now > self.wall {
+            Self::new(now)
+        } else {
+            self.successor()
+        };
+
+        *self
+    }
+
+    /// Advance the clock past a timestamp received from another actor, given
+    /// the current time of the local wall-clock, and return the new timestamp.
+    pub fn observe(&mut self, remote: Self, now: u64) -> Self {
+        let wall = now.max(self.wall).max(remote.wall);
+
+        *self = match (wall == self.wall, wall == remote.wall) {
+            (true, true) => (*self).max(remote).successor(),
+            (true, false) => self.successor(),
+            (false, true) => remote.successor(),
+            (false, false) => Self::new(wall),
+        };
+
+        *self
+    }
+
+    // The next timestamp, which carries into the wall-clock time once the
+    // logical counter is exhausted.
+    fn successor(self) -> Self {
+        match self.logical.checked_add(1) {
+            Some(logical) => Self { logical, ..self },
+            None => Self::new(self.wall.saturating_add(1)),
+        }
+    }
+}
+
+impl Semilattice for Hlc {
+    fn join(self, other: Self) -> Self {
+        self.max(other)
+    }
+}
+
+impl JoinRef for Hlc {}
+
+impl Decompose for Hlc {
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if self != Self::default() {
+            func(self)
+        }
+    }
+}
+
+/// A last-writer-wins register. Writes are ordered by their timestamp, then
+/// by the actor which wrote them, so that concurrent writes resolve to the
+/// same winner on every replica. The value only breaks ties between writes
+/// with the same timestamp and actor, which should not happen.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct Lww<A, T> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub inner: Option<(Hlc, A, T)>,
+}
+
+impl<A, T> Lww<A, T>
+where
+    A: Ord,
+    T: Ord,
+{
+    pub fn new(timestamp: Hlc, actor: A, value: T) -> Self {
+        Self {
+            inner: Some((timestamp, actor, value)),
+        }
+    }
+
+    /// Write a value, unless a later write is already known.
+    pub fn set(&mut self, timestamp: Hlc, actor: A, value: T) {
+        self.join_assign(Self::new(timestamp, actor, value));
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        self.inner.as_ref().map(|(_, _, value)| value)
+    }
+
+    pub fn timestamp(&self) -> Option<Hlc> {
+        self.inner.as_ref().map(|(timestamp, _, _)| *timestamp)
+    }
+
+    pub fn actor(&self) -> Option<&A> {
+        self.inner.as_ref().map(|(_, actor, _)| actor)
+    }
+}
+
+impl<A, T> Default for Lww<A, T> {
+    fn default() -> Self {
+        Self { inner: None }
+    }
+}
+
+impl<A, T> Semilattice for Lww<A, T>
+where
+    A: Ord,
+    T: Ord,
+{
+    fn join(self, other: Self) -> Self {
+        match self.cmp(&other) {
+            Ordering::Less => other,
+            _ => self,
+        }
+    }
+}
+
+impl<A, T> JoinRef for Lww<A, T>
+where
+    A: Ord + Clone,
+    T: Ord + Clone,
+{
+}
+
+impl<A, T> Decompose for Lww<A, T>
+where
+    A: Ord,
+    T: Ord,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if self.inner.is_some() {
+            func(self)
+        }
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{fold, partially_verify_decomposition, partially_verify_semilattice_laws};
+
+    let mut alice = Hlc::default();
+    let mut bob = Hlc::default();
+
+    let a = Lww::new(alice.tick(100), "alice", "Issue with feature X");
+    // bob's clock is behind, but he has seen alice's write.
+    let b = Lww::new(bob.observe(alice, 90), "bob", "Issue with feature Y");
+    // alice writes concurrently, within the same tick of her clock.
+    let c = Lww::new(alice.tick(100), "alice", "Issue with feature Z");
+
+    assert_eq!(
+        b.timestamp(),
+        Some(Hlc {
+            wall: 100,
+            logical: 1
+        })
+    );
+    assert_eq!(
+        c.timestamp(),
+        Some(Hlc {
+            wall: 100,
+            logical: 1
+        })
+    );
+
+    // equal timestamps are resolved by the actor.
+    let d = fold([a.clone(), b.clone(), c.clone()]);
+    assert_eq!(d.get(), Some(&"Issue with feature Y"));
+    assert_eq!(d.actor(), Some(&"bob"));
+
+    assert_eq!(
+        alice.observe(bob, 95),
+        Hlc {
+            wall: 100,
+            logical: 2
+        }
+    );
+    assert_eq!(alice.tick(101), Hlc::new(101));
+
+    // an exhausted logical counter carries into the wall-clock time.
+    let mut carol = Hlc {
+        wall: 101,
+        logical: u32::MAX,
+    };
+    assert_eq!(carol.tick(100), Hlc::new(102));
+    let exhausted = Hlc {
+        wall: 102,
+        logical: u32::MAX,
+    };
+    assert_eq!(carol.observe(exhausted, 100), Hlc::new(103));
+
+    partially_verify_semilattice_laws([a.clone(), b.clone(), c.clone(), d.clone()]);
+    partially_verify_semilattice_laws([Hlc::new(5), alice, bob]);
+    partially_verify_decomposition([a, b, c, d]);
+}
@@ -1,9 +1,9 @@
 use alloc::{borrow::ToOwned, vec, vec::Vec};
 use core::{borrow::Borrow, cmp, mem, ops};
 
-use crate::{DeferredRestore, Semilattice};
+use crate::{Decompose, DeferredRestore, JoinRef, Lattice, Semilattice};
 
-fn gallop<T>(mut slice: &[T], mut cmp: impl FnMut(&T) -> bool) -> &[T] {
+pub(crate) fn gallop<T>(mut slice: &[T], mut cmp: impl FnMut(&T) -> bool) -> &[T] {
     // if empty slice, or already >= element, return
     if !slice.is_empty() && cmp(&slice[0]) {
         let mut step = 1;
@@ -27,13 +27,115 @@ fn gallop<T>(mut slice: &[T], mut cmp: impl FnMut(&T) -> bool) -> &[T] {
     slice
 }
 
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[cfg_attr(
-    feature = "minicbor",
-    derive(minicbor::Encode, minicbor::Decode),
-    cbor(transparent)
-)]
+// Merge the sorted entries of `other` into `vec`, galloping over the runs of
+// `vec` between them. Matching entries are joined in place, and only the
+// entries after the first inserted key are moved.
+pub(crate) fn merge<K, V>(vec: &mut Vec<(K, V)>, other: Vec<(K, V)>, inflated: impl FnMut(&K))
+where
+    K: Ord,
+    V: Semilattice,
+{
+    merge_by(
+        vec,
+        other,
+        |(k, _)| k,
+        |(_, v), (_, v2)| v.join_assign_changed(v2),
+        inflated,
+    )
+}
+
+// Merge the sorted entries of `other` into `vec` by their keys, where `join`
+// joins an entry into one with the same key and returns whether it changed.
+pub(crate) fn merge_by<T, K>(
+    vec: &mut Vec<T>,
+    other: Vec<T>,
+    key: impl Fn(&T) -> &K,
+    mut join: impl FnMut(&mut T, T) -> bool,
+    mut inflated: impl FnMut(&K),
+) where
+    K: Ord,
+{
+    let mut inserted: Vec<(usize, T)> = Vec::new();
+    // the index of the last entry of `vec` which was reported, so that the
+    // duplicate keys of `other` are reported once.
+    let mut reported = None;
+    let mut i = 0;
+
+    for entry in other {
+        i = vec.len() - gallop(&vec[i..], |x| key(x) < key(&entry)).len();
+
+        match (vec.get_mut(i), inserted.last_mut()) {
+            (Some(x), _) if key(x) == key(&entry) => {
+                if join(x, entry) && reported != Some(i) {
+                    reported = Some(i);
+                    inflated(key(x));
+                }
+            }
+            // a duplicate key in `other`.
+            (_, Some((j, x))) if *j == i && key(x) == key(&entry) => {
+                join(x, entry);
+            }
+            _ => {
+                inflated(key(&entry));
+                inserted.push((i, entry));
+            }
+        }
+    }
+
+    insert_sorted(vec, inserted);
+}
+
+// Like `merge_by`, but from borrowed entries, so that only those which are
+// inserted are cloned. `join` joins a borrowed entry into one with the same
+// key.
+pub(crate) fn merge_by_ref<'a, T, K>(
+    vec: &mut Vec<T>,
+    other: impl IntoIterator<Item = &'a T>,
+    key: impl Fn(&T) -> &K,
+    mut join: impl FnMut(&mut T, &T),
+) where
+    T: Clone + 'a,
+    K: Ord,
+{
+    let mut inserted: Vec<(usize, T)> = Vec::new();
+    let mut i = 0;
+
+    for entry in other {
+        i = vec.len() - gallop(&vec[i..], |x| key(x) < key(entry)).len();
+
+        match (vec.get_mut(i), inserted.last_mut()) {
+            (Some(x), _) if key(x) == key(entry) => join(x, entry),
+            // a duplicate key in `other`.
+            (_, Some((j, x))) if *j == i && key(x) == key(entry) => join(x, entry),
+            _ => inserted.push((i, entry.clone())),
+        }
+    }
+
+    insert_sorted(vec, inserted);
+}
+
+// Insert each entry at its index of `vec`, which are in ascending order.
+fn insert_sorted<T>(vec: &mut Vec<T>, inserted: Vec<(usize, T)>) {
+    if let Some(&(first, _)) = inserted.first() {
+        let mut old = vec.split_off(first).into_iter();
+        vec.reserve(old.len() + inserted.len());
+        let mut end = first;
+
+        for (i, entry) in inserted {
+            vec.extend(old.by_ref().take(i - end));
+            vec.push(entry);
+            end = i;
+        }
+
+        vec.extend(old);
+    }
+}
+
+/// A map whose keys are joined by their values. A key with a bottom value is
+/// the same as a missing key, for comparisons and encoding.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Decode), cbor(transparent))]
 pub struct MapLattice<K, V> {
     #[cfg_attr(feature = "minicbor", n(0))]
     pub inner: Vec<(K, V)>,
@@ -53,6 +155,7 @@ where
     pub fn insert(&mut self, key: K, val: V) {
         match self.inner.binary_search_by(|(k, _)| k.cmp(&key)) {
             Ok(i) => self.inner[i].1.join_assign(val),
+            Err(_) if val.is_bottom() => (),
             Err(i) => self.inner.insert(i, (key, val)),
         }
     }
@@ -83,14 +186,68 @@ where
 
         &mut self.inner[i].1
     }
+
+    /// Remove the keys with bottom values, such as those left behind by
+    /// `entry_mut`, which are already treated as missing.
+    pub fn normalize(&mut self) {
+        self.inner.retain(|(_, v)| !v.is_bottom());
+    }
+}
+
+/// The keys, elements or indices which were inflated by a join.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinReport<K> {
+    pub inflated: Vec<K>,
+}
+
+impl<K> JoinReport<K> {
+    pub fn changed(&self) -> bool {
+        !self.inflated.is_empty()
+    }
+}
+
+impl<K> Default for JoinReport<K> {
+    fn default() -> Self {
+        Self {
+            inflated: Vec::default(),
+        }
+    }
+}
+
+impl<K, V> MapLattice<K, V>
+where
+    K: Ord,
+    V: Semilattice,
+{
+    /// Join `other` into `self`, reporting each key whose value was inflated
+    /// or which was newly added.
+    pub fn join_assign_report(&mut self, mut other: Self) -> JoinReport<K>
+    where
+        K: Clone,
+    {
+        let mut report = JoinReport::default();
+        other.normalize();
+        merge(&mut self.inner, other.inner, |k| {
+            report.inflated.push(k.clone())
+        });
+        report
+    }
 }
 
 impl<K, V> From<Vec<(K, V)>> for MapLattice<K, V>
 where
     K: Ord,
+    V: Semilattice,
 {
     fn from(mut inner: Vec<(K, V)>) -> Self {
         inner.sort_unstable_by(|(k1, _), (k2, _)| k1.cmp(k2));
+        // join the values of duplicate keys into the first of them.
+        inner.dedup_by(|(k1, v1), (k2, v2)| {
+            k1 == k2 && {
+                v2.join_assign(mem::take(v1));
+                true
+            }
+        });
 
         Self { inner }
     }
@@ -124,31 +281,52 @@ where
     V: Semilattice,
 {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        let mut greater = self.inner.len() > other.inner.len();
-        let mut less = self.inner.len() < other.inner.len();
-
-        // FIXME: optimize using gallop
-        for (k1, _) in &self.inner {
-            if let None = other.entry(k1) {
-                // other is missing a key from self
-                greater = true;
+        let mut greater = false;
+        let mut less = false;
+
+        let mut a = &self.inner[..];
+        let mut b = &other.inner[..];
+
+        // whether a run of keys, which the other map is missing, has any
+        // which are present.
+        let present = |run: &[(K, V)]| run.iter().any(|(_, v)| !v.is_bottom());
+
+        // a single pass over both sorted vectors, galloping over the keys
+        // which only one of them has.
+        while let (Some((k1, v1)), Some((k2, v2))) = (a.first(), b.first()) {
+            match k1.cmp(k2) {
+                cmp::Ordering::Less => {
+                    // other is missing keys from self
+                    let rest = gallop(a, |(k, _)| k < k2);
+                    greater |= present(&a[..a.len() - rest.len()]);
+                    a = rest;
+                }
+                cmp::Ordering::Greater => {
+                    // self is missing keys in other
+                    let rest = gallop(b, |(k, _)| k < k1);
+                    less |= present(&b[..b.len() - rest.len()]);
+                    b = rest;
+                }
+                cmp::Ordering::Equal => {
+                    match v1.partial_cmp(v2) {
+                        Some(cmp::Ordering::Less) => less = true,
+                        Some(cmp::Ordering::Greater) => greater = true,
+                        Some(cmp::Ordering::Equal) => (),
+                        None => return None,
+                    }
+                    a = &a[1..];
+                    b = &b[1..];
+                }
             }
-        }
 
-        for (k2, v2) in &other.inner {
-            if let Some(v1) = self.entry(k2) {
-                match v1.partial_cmp(v2) {
-                    Some(cmp::Ordering::Less) if !greater => less = true,
-                    Some(cmp::Ordering::Greater) if !less => greater = true,
-                    Some(cmp::Ordering::Equal) => (),
-                    _ => return None,
-                }
-            } else {
-                // self is missing a key in other
-                less = true;
+            if greater && less {
+                return None;
             }
         }
 
+        greater |= present(a);
+        less |= present(b);
+
         if greater && less {
             None
         } else {
@@ -163,34 +341,107 @@ where
     V: Semilattice,
 {
     fn join(mut self, mut other: Self) -> Self {
-        match self.partial_cmp(&other) {
-            Some(cmp::Ordering::Greater | cmp::Ordering::Equal) => self,
-            Some(cmp::Ordering::Less) => other,
-            None => {
-                self.inner.append(&mut other.inner);
-
-                // sort is faster than unstable_sort with sequences of sorted tuples.
-                self.inner.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
-                let (dedup, dups) = self.inner.partition_dedup_by(|(k1, _), (k2, _)| k1 == k2);
-
-                // merge all non-ZST values.
-                if mem::size_of::<V>() > 0 {
-                    // partition_dedup_by maintains the order of `dedup` but does
-                    // not define the order of `dups`.
-                    for dup in dups {
-                        dedup[dedup
-                            .binary_search_by(|x| x.0.cmp(&dup.0))
-                            .expect("dedup contains dups by definition")]
-                        .1
-                        .join_assign(core::mem::take(&mut dup.1));
-                    }
-                }
+        // merge the smaller map into the larger.
+        if self.inner.len() < other.inner.len() {
+            mem::swap(&mut self, &mut other);
+        }
+
+        other.normalize();
+        merge(&mut self.inner, other.inner, |_| ());
+        self
+    }
 
-                let len = dedup.len();
-                self.inner.truncate(len);
+    fn join_assign_changed(&mut self, mut other: Self) -> bool {
+        let mut changed = false;
+        other.normalize();
+        merge(&mut self.inner, other.inner, |_| changed = true);
+        changed
+    }
 
-                self
+    fn leq(&self, other: &Self) -> bool {
+        // every entry of `self` is below the one of `other`, galloping over
+        // the keys which only `other` has.
+        let mut b = &other.inner[..];
+        self.inner.iter().all(|(k, v)| {
+            b = gallop(b, |(k2, _)| k2 < k);
+            match b.first() {
+                Some((k2, v2)) if k2 == k => v.leq(v2),
+                _ => v.is_bottom(),
             }
+        })
+    }
+
+    fn is_bottom(&self) -> bool {
+        self.present().next().is_none()
+    }
+
+    fn delta(mut self, since: &Self) -> Self {
+        // keep keys which are missing from `since`, and the deltas of those
+        // which have grown.
+        self.inner = mem::take(&mut self.inner)
+            .into_iter()
+            .filter_map(|(k, v)| match since.entry(&k) {
+                None if v.is_bottom() => None,
+                None => Some((k, v)),
+                Some(s) if v.leq(s) => None,
+                Some(s) => Some((k, v.delta(s))),
+            })
+            .collect();
+
+        self
+    }
+}
+
+impl<K, V> JoinRef for MapLattice<K, V>
+where
+    K: Ord + Clone,
+    V: JoinRef,
+{
+    fn join_assign_ref(&mut self, other: &Self) {
+        // only the entries which are inserted are cloned, and those which are
+        // joined clone what they need themselves.
+        merge_by_ref(
+            &mut self.inner,
+            other.iter().filter(|(_, v)| !v.is_bottom()),
+            |(k, _)| k,
+            |(_, v), (_, v2)| v.join_assign_ref(v2),
+        )
+    }
+}
+
+impl<K, V> Lattice for MapLattice<K, V>
+where
+    K: Ord,
+    V: Lattice,
+{
+    fn meet(self, other: Self) -> Self {
+        // only keys in both maps, with the meet of their values.
+        let mut other = other.inner.into_iter().peekable();
+
+        let inner = self
+            .inner
+            .into_iter()
+            .filter_map(|(k, v)| {
+                while other.next_if(|(k2, _)| *k2 < k).is_some() {}
+                other
+                    .next_if(|(k2, _)| *k2 == k)
+                    .map(|(_, v2)| (k, v.meet(v2)))
+                    .filter(|(_, v)| !v.is_bottom())
+            })
+            .collect();
+
+        Self { inner }
+    }
+}
+
+impl<K, V> Decompose for MapLattice<K, V>
+where
+    K: Ord + Clone,
+    V: Decompose,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        for (k, v) in self.inner {
+            v.decompose(|part| func(Self::singleton(k.clone(), part)));
         }
     }
 }
@@ -201,7 +452,59 @@ where
     V: Semilattice,
 {
     fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
-        Self::from(Vec::from_iter(iter))
+        let mut map = Self::from(Vec::from_iter(iter));
+        map.normalize();
+        map
+    }
+}
+
+impl<K, V> MapLattice<K, V>
+where
+    V: Semilattice,
+{
+    // The entries which are not treated as missing keys.
+    fn present(&self) -> impl Iterator<Item = &(K, V)> {
+        self.inner.iter().filter(|(_, v)| !v.is_bottom())
+    }
+}
+
+impl<K, V> PartialEq for MapLattice<K, V>
+where
+    K: PartialEq,
+    V: Semilattice,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.present().eq(other.present())
+    }
+}
+
+/// Encoded like its entries, without the bottom values.
+#[cfg(feature = "minicbor")]
+impl<K, V> minicbor::Encode for MapLattice<K, V>
+where
+    K: minicbor::Encode,
+    V: Semilattice + minicbor::Encode,
+{
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(self.present().count() as u64)?;
+        for entry in self.present() {
+            e.encode(entry)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for MapLattice<K, V>
+where
+    K: serde::Serialize,
+    V: Semilattice + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.present())
     }
 }
 
@@ -251,65 +554,27 @@ where
     }
 
     fn restore(&mut self) -> bool {
-        fn merge<K, V>(mut vec: Vec<(K, V)>, mut other: Vec<(K, V)>) -> Vec<(K, V)>
+        fn merge_batches<K, V>(mut vec: Vec<(K, V)>, mut other: Vec<(K, V)>) -> Vec<(K, V)>
         where
             K: Ord,
             V: Semilattice,
         {
-            match (vec.last(), other.first(), other.last(), vec.first()) {
-                // other is empty
-                (_, None, _, _) => vec,
-                // vec is empty
-                (None, _, _, _) => other,
-                // vec is a prefix of other
-                (Some(a), Some(b), _, _) if a.0 <= b.0 => {
-                    vec.append(&mut other);
-                    vec
-                }
-                // vec is a suffix of other
-                (_, _, Some(c), Some(d)) if c.0 <= d.0 => {
-                    other.append(&mut vec);
-                    other
-                }
-                // neither are empty nor a prefix of the other
-                _ => {
-                    // NOTE: Would prefer to not copy the (visually correct)
-                    // unsafe code from Datafrog because we can probably do a
-                    // fair bit better using `gallop` to partition runs.  If
-                    // `other` only "updates" elements already in `vec`, or it
-                    // only introduces new elements near the end, then we don't
-                    // need a new vector.
-
-                    // sort_by is faster than unstable_sort_by when sorting
-                    // sequences of sorted vectors
-                    vec.append(&mut other);
-                    vec.sort_by(|x, y| x.0.cmp(&y.0));
-
-                    let (dedup, dups) = vec.partition_dedup_by(|x, y| x.0 == y.0);
-
-                    // partition_dedup_by maintains the order of `dedup` but
-                    // does not define the order of `dups`.
-                    for dup in dups {
-                        dedup[dedup
-                            .binary_search_by(|x| x.0.cmp(&dup.0))
-                            .expect("dedup contains dups by definition")]
-                        .1
-                        .join_assign(core::mem::take(&mut dup.1));
-                    }
-
-                    let len = dedup.len();
-                    vec.truncate(len);
-
-                    vec
-                }
+            // merge the smaller batch into the larger, which is only moved
+            // from the first new key onwards; if `other` only updates keys of
+            // `vec`, or adds keys after them, nothing is moved at all.
+            if vec.len() < other.len() {
+                mem::swap(&mut vec, &mut other);
             }
+
+            merge(&mut vec, other, |_| ());
+            vec
         }
 
         // 1. Merge self.recent into self.stable.
         if !self.recent.is_empty() {
             let mut recent = core::mem::take(&mut self.recent);
             while self.stable.last().map(|x| x.len() <= 2 * recent.len()) == Some(true) {
-                recent = merge(
+                recent = merge_batches(
                     recent,
                     self.stable.pop().expect("We just checked last exists"),
                 );
@@ -322,17 +587,24 @@ where
         // 2a. Restore ordering for `self.to_add`
         let mut to_add = mem::take(&mut self.to_add);
         to_add.sort_by(|x, y| x.0.cmp(&y.0));
+        // batches have unique keys, so that they can be merged in place.
+        to_add.dedup_by(|x, y| {
+            let dup = x.0 == y.0;
+            if dup {
+                y.1.join_assign(mem::take(&mut x.1));
+            }
+            dup
+        });
 
-        // 2b. filter elements which are already greater in stable
+        // 2b. filter elements which are already below the stable values of
+        // their keys; the rest are joined into them in step 1.
         for batch in &self.stable {
             let mut slice = &batch[..];
             to_add.retain(|x| {
                 slice = gallop(slice, |y| y.0 < x.0);
-                if let Some(y) = slice.first() {
-                    y.0 != x.0 || matches!(x.1.partial_cmp(&y.1), None | Some(cmp::Ordering::Less))
-                } else {
-                    false
-                }
+                // keep keys past the end of the batch, and values which are
+                // not already below the stable one.
+                slice.first().is_none_or(|y| y.0 != x.0 || !x.1.leq(&y.1))
             });
         }
         self.recent = to_add;
@@ -361,7 +633,7 @@ where
 
 #[test]
 fn check_laws() {
-    use crate::{partially_verify_semilattice_laws, Max};
+    use crate::{partially_verify_decomposition, partially_verify_distributive_lattice_laws, Max};
 
     let a = MapLattice::from_iter([("Alice", Max(123)), ("Bob", Max(50))]);
     let b = MapLattice::from_iter([("Bob", Max(300)), ("Carol", Max(100))]);
@@ -374,5 +646,186 @@ fn check_laws() {
         MapLattice::from_iter([("Alice", Max(123)), ("Bob", Max(300)), ("Carol", Max(150)),])
     );
 
-    partially_verify_semilattice_laws([a, b, c, d]);
+    assert_eq!(
+        d.clone().delta(&a),
+        MapLattice::from_iter([("Bob", Max(300)), ("Carol", Max(150))])
+    );
+
+    assert_eq!(
+        d.clone().meet(b.clone()),
+        MapLattice::from_iter([("Bob", Max(300)), ("Carol", Max(100))])
+    );
+    assert_eq!(
+        a.clone().meet(b.clone()),
+        MapLattice::from_iter([("Bob", Max(50))])
+    );
+
+    // small deltas merged into a large map, at the start, middle and end.
+    let large = MapLattice::from_iter((0..1000).map(|i| (i * 2, Max(i))));
+    for key in [-1, 0, 999, 1000, 1998, 1999, 5000] {
+        let delta = MapLattice::singleton(key, Max(2000));
+        let joined = large.clone().join(delta.clone());
+        assert_eq!(joined, delta.clone().join(large.clone()));
+        let mut borrowed = large.clone();
+        borrowed.join_assign_ref(&delta);
+        assert_eq!(borrowed, joined);
+        assert!(large < joined && delta < joined);
+        assert_eq!(joined.entry(&key), Some(&Max(2000)));
+        assert_eq!(joined.len(), 1000 + (key % 2 != 0 || key > 1998) as usize);
+        assert_eq!(large.partial_cmp(&delta), None);
+    }
+
+    // keys with bottom values are the same as missing keys.
+    let mut e = a.clone();
+    e.entry_mut(&"Dave");
+    assert_eq!(e, a);
+    assert_eq!(e.partial_cmp(&a), Some(cmp::Ordering::Equal));
+    assert!(!e.join_assign_changed(MapLattice::singleton("Erin", Max(i32::MIN))));
+    e.join_assign_ref(&MapLattice::singleton("Erin", Max(i32::MIN)));
+    assert_eq!(e.entry(&"Erin"), None);
+    assert!(e.leq(&a) && a.leq(&e) && !d.leq(&e));
+    assert!(MapLattice::singleton("Erin", Max(i32::MIN)).is_bottom());
+    assert!(MapLattice::singleton("Erin", ()).is_bottom());
+    #[cfg(feature = "minicbor")]
+    {
+        let encode = |map: &MapLattice<_, _>| {
+            let mut bytes = Vec::new();
+            minicbor::encode(map, &mut bytes).unwrap();
+            bytes
+        };
+        assert_eq!(encode(&e), encode(&a));
+    }
+    e.normalize();
+    assert_eq!(e.inner, a.inner);
+
+    // duplicate keys are joined, both when collected and when merged.
+    let dup = MapLattice::from_iter([(1, Max(1u8)), (1, Max(2))]);
+    assert_eq!(dup.inner, [(1, Max(2))]);
+    assert_eq!(
+        MapLattice::from_iter([(1, Max(5))]).join(dup).inner,
+        [(1, Max(5))]
+    );
+    let dup = MapLattice {
+        inner: vec![(1, Max(2u8)), (1, Max(3)), (2, Max(1)), (2, Max(4))],
+    };
+    let mut f = MapLattice::from_iter([(1, Max(1))]);
+    assert_eq!(f.join_assign_report(dup.clone()).inflated, [1, 2]);
+    assert_eq!(f.inner, [(1, Max(3)), (2, Max(4))]);
+    let mut f = MapLattice::from_iter([(1, Max(1))]);
+    f.join_assign_ref(&dup);
+    assert_eq!(f.inner, [(1, Max(3)), (2, Max(4))]);
+
+    partially_verify_distributive_lattice_laws([a.clone(), b.clone(), c.clone(), d.clone()]);
+    partially_verify_decomposition([a, b, c, d]);
+}
+
+#[test]
+fn check_join_assign_ref() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{Max, SetLattice, VecLattice};
+
+    static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+    // a value which counts how often it is cloned.
+    #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    struct Counted(Max<u8>);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.fetch_add(1, Ordering::Relaxed);
+            Self(self.0)
+        }
+    }
+
+    impl Semilattice for Counted {
+        fn join(self, other: Self) -> Self {
+            Self(self.0.join(other.0))
+        }
+    }
+
+    impl JoinRef for Counted {}
+
+    let counted = |n| (0..100u8).map(move |i| Counted(Max(i % n)));
+
+    // only the entry which grows and the one which is inserted are cloned.
+    let mut local = MapLattice::from_iter((0..100).zip(counted(3)));
+    let mut remote = MapLattice::from_iter((0..100).zip(counted(3)));
+    remote.insert(50, Counted(Max(7)));
+    remote.insert(500, Counted(Max(1)));
+
+    CLONES.store(0, Ordering::Relaxed);
+    local.join_assign_ref(&remote);
+    assert_eq!(CLONES.load(Ordering::Relaxed), 2);
+    assert_eq!(local, remote);
+
+    let mut local = SetLattice::from_iter(counted(100));
+    let mut remote = SetLattice::from_iter(counted(100));
+    remote.insert(Counted(Max(200)));
+
+    CLONES.store(0, Ordering::Relaxed);
+    local.join_assign_ref(&remote);
+    assert_eq!(CLONES.load(Ordering::Relaxed), 1);
+    assert_eq!(local, remote);
+
+    let mut local = VecLattice {
+        inner: counted(3).collect(),
+    };
+    let mut remote = local.clone();
+    remote.inner[50] = Counted(Max(7));
+    remote.inner.push(Counted(Max(1)));
+
+    CLONES.store(0, Ordering::Relaxed);
+    local.join_assign_ref(&remote);
+    assert_eq!(CLONES.load(Ordering::Relaxed), 2);
+    assert_eq!(local, remote);
+}
+
+#[test]
+fn check_restore() {
+    use crate::Max;
+
+    let mut map = Map::default();
+
+    // batches which append, update, and insert between existing keys.
+    let batches = [
+        vec![(0, 1), (2, 1), (4, 1)],
+        vec![(6, 1), (8, 1)],
+        vec![(2, 5), (8, 3), (8, 2)],
+        vec![(1, 1), (5, 1), (9, 1), (1, 4)],
+        vec![(0, 0), (4, 1), (6, 2)],
+    ];
+
+    for batch in batches {
+        for (k, v) in batch {
+            map.insert((k, Max(v)));
+        }
+        while map.restore() {}
+    }
+
+    assert!(map.recent.is_empty() && map.to_add.is_empty());
+
+    let mut stable = MapLattice::default();
+    map.for_each_stable(|(k, v)| stable.insert(*k, *v));
+    assert_eq!(
+        stable,
+        MapLattice::from_iter(
+            [
+                (0, 1),
+                (1, 4),
+                (2, 5),
+                (4, 1),
+                (5, 1),
+                (6, 2),
+                (8, 3),
+                (9, 1)
+            ]
+            .map(|(k, v)| (k, Max(v)))
+        )
+    );
+
+    // each batch is sorted with unique keys.
+    for batch in &map.stable {
+        assert!(batch.windows(2).all(|w| w[0].0 < w[1].0));
+    }
 }
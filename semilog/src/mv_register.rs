@@ -0,0 +1,167 @@
+use core::cmp;
+
+use alloc::{vec, vec::Vec};
+
+use crate::{fold, JoinRef, Semilattice, VersionVector};
+
+/// A multi-value register. Each write is tagged with the version vector of the
+/// writer, and keeps every concurrently written value until a causally later
+/// write replaces them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct MvRegister<A, T> {
+    // Causally maximal writes, sorted by version vector and value.
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub inner: Vec<(VersionVector<A>, T)>,
+}
+
+// Whether the write `a` is known by the write `b`.
+fn dominated<A: Ord, T: PartialEq>(a: &(VersionVector<A>, T), b: &(VersionVector<A>, T)) -> bool {
+    match a.0.partial_cmp(&b.0) {
+        Some(cmp::Ordering::Less) => true,
+        Some(cmp::Ordering::Equal) => a.1 == b.1,
+        _ => false,
+    }
+}
+
+impl<A, T> MvRegister<A, T>
+where
+    A: Ord + Clone,
+    T: Ord,
+{
+    /// Replace every value known to this replica with a single value written
+    /// by `actor`.
+    pub fn set(&mut self, actor: A, value: T) {
+        let mut context = self.context();
+        context.increment(actor);
+
+        self.inner = vec![(context, value)];
+    }
+
+    /// The version vector of all known writes.
+    pub fn context(&self) -> VersionVector<A> {
+        fold(self.inner.iter().map(|(vv, _)| vv.clone()))
+    }
+
+    /// The concurrently written values.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.inner.iter().map(|(_, v)| v)
+    }
+
+    /// Whether concurrent writes are in conflict.
+    pub fn is_conflicted(&self) -> bool {
+        self.inner.len() > 1
+    }
+}
+
+impl<A, T> Default for MvRegister<A, T> {
+    fn default() -> Self {
+        Self {
+            inner: Vec::default(),
+        }
+    }
+}
+
+impl<A, T> PartialOrd for MvRegister<A, T>
+where
+    A: Ord,
+    T: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        let covered = |a: &Self, b: &Self| {
+            a.inner
+                .iter()
+                .all(|x| b.inner.iter().any(|y| dominated(x, y)))
+        };
+
+        match (covered(self, other), covered(other, self)) {
+            (true, true) => Some(cmp::Ordering::Equal),
+            (true, false) => Some(cmp::Ordering::Less),
+            (false, true) => Some(cmp::Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+impl<A, T> Semilattice for MvRegister<A, T>
+where
+    A: Ord,
+    T: Ord,
+{
+    fn join(mut self, other: Self) -> Self {
+        self.inner.extend(other.inner);
+
+        // keep only the causally maximal writes, in a canonical order. Equal
+        // version vectors may have different entries until normalized, and
+        // would otherwise not be deduplicated.
+        for (vv, _) in &mut self.inner {
+            vv.inner.normalize();
+        }
+        self.inner
+            .sort_by(|(vv1, v1), (vv2, v2)| (&vv1.inner.inner, v1).cmp(&(&vv2.inner.inner, v2)));
+        self.inner.dedup();
+
+        let maximal: Vec<bool> = self
+            .inner
+            .iter()
+            .map(|x| !self.inner.iter().any(|y| x != y && dominated(x, y)))
+            .collect();
+        let mut maximal = maximal.into_iter();
+        self.inner.retain(|_| maximal.next() == Some(true));
+
+        self
+    }
+}
+
+impl<A, T> JoinRef for MvRegister<A, T>
+where
+    A: Ord + Clone,
+    T: Ord + Clone,
+{
+}
+
+#[test]
+fn check_laws() {
+    use crate::partially_verify_semilattice_laws;
+
+    let mut a = MvRegister::default();
+    a.set("alice", "Issue with feature X");
+
+    // bob and carol edit the title concurrently.
+    let mut b = a.clone();
+    b.set("bob", "Issue with feature Y");
+    let mut c = a.clone();
+    c.set("carol", "Issue with feature Z");
+
+    let mut d = b.clone().join(c.clone());
+    assert!(d.is_conflicted());
+    assert_eq!(
+        d.values().collect::<Vec<_>>(),
+        [&"Issue with feature Y", &"Issue with feature Z"]
+    );
+    assert_eq!(a.clone().join(d.clone()), d);
+    let conflicted = d.clone();
+
+    // alice resolves the conflict, having seen both edits.
+    let mut e = d.clone();
+    e.set("alice", "Issue with features Y and Z");
+    assert!(!e.is_conflicted());
+    assert_eq!(e.context().get("alice"), 2);
+
+    partially_verify_semilattice_laws([a, b, c, d.clone(), e.clone()]);
+
+    d.join_assign(e.clone());
+    assert_eq!(d, e);
+
+    // a version vector with an explicit zero entry is the same write, even
+    // though it sorts apart from the original.
+    let mut f = conflicted.clone();
+    f.inner[1].0.inner.entry_mut(&"aaron");
+    assert_eq!(f.clone().join(conflicted.clone()).inner, conflicted.inner);
+    assert_eq!(conflicted.clone().join(f).inner, conflicted.inner);
+}
@@ -0,0 +1,440 @@
+use alloc::vec::Vec;
+use core::{borrow::Borrow, cmp};
+
+use crate::{
+    fold,
+    map::{gallop, merge},
+    Dot, JoinRef, MapLattice, Semilattice, SetLattice, VersionVector,
+};
+
+/// An observed-remove map. Each write of a key is tagged with a unique dot,
+/// and the causal context records every dot which has been seen. A removal
+/// drops the dots of a key which it has observed, so a concurrent write of the
+/// same key wins and keeps only its own value.
+///
+/// Every dot of `entries` must be contained in `context`, and no key may be
+/// left without dots. A dot with a bottom value is the same as a missing one,
+/// so a set of keys is an `OrSet` rather than a map to `()`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "A: Ord + serde::Serialize, K: serde::Serialize, V: Semilattice + serde::Serialize"
+    ))
+)]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
+pub struct OrMap<A, K, V> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    #[cfg_attr(
+        feature = "minicbor",
+        cbor(encode_bound = "V: Semilattice + minicbor::Encode")
+    )]
+    pub entries: MapLattice<K, MapLattice<Dot<A>, V>>,
+    #[cfg_attr(feature = "minicbor", n(1))]
+    #[cfg_attr(feature = "minicbor", cbor(encode_bound = "A: Ord + minicbor::Encode"))]
+    pub context: VersionVector<A>,
+}
+
+// Keep the entries for which `func` returns true, given the entry of `other`
+// with the same key, if any, which is found by galloping through it.
+fn retain_by_key<K, V>(
+    entries: &mut Vec<(K, V)>,
+    other: &[(K, V)],
+    mut func: impl FnMut(&mut V, Option<&V>) -> bool,
+) where
+    K: Ord,
+{
+    let mut rest = other;
+    entries.retain_mut(|(k, v)| {
+        rest = gallop(rest, |(k2, _)| k2 < k);
+        func(v, rest.first().filter(|(k2, _)| k2 == k).map(|(_, v2)| v2))
+    });
+}
+
+impl<A, K, V> OrMap<A, K, V>
+where
+    A: Ord + Clone,
+    K: Ord,
+    V: Semilattice + Clone,
+{
+    /// Write `value` to `key` as `actor`, joined with its current value.
+    pub fn insert(&mut self, actor: A, key: K, value: V) {
+        let dot = self.context.increment(actor);
+
+        match self.entries.binary_search_by(|(k, _)| k.cmp(&key)) {
+            Ok(i) => {
+                let dots = &mut self.entries.inner[i].1;
+                let value = fold(dots.iter().map(|(_, v)| v.clone())).join(value);
+                *dots = MapLattice::singleton(dot, value);
+            }
+            Err(i) => self
+                .entries
+                .inner
+                .insert(i, (key, MapLattice::singleton(dot, value))),
+        }
+    }
+
+    /// Remove `key`, unless it is concurrently written by another replica.
+    pub fn remove<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        if let Ok(i) = self.entries.binary_search_by(|(k, _)| k.borrow().cmp(key)) {
+            self.entries.inner.remove(i);
+        }
+    }
+
+    /// The value of `key`, which is the join of every concurrent write.
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.entries
+            .entry(key)
+            .map(|dots| fold(dots.iter().map(|(_, v)| v.clone())))
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.entries.entry(key).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, V)> {
+        self.entries
+            .iter()
+            .map(|(k, dots)| (k, fold(dots.iter().map(|(_, v)| v.clone()))))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<A, K, V> OrMap<A, K, V>
+where
+    A: Ord,
+    K: Ord,
+    V: Semilattice,
+{
+    // Whether joining `self` into `other` would leave it unchanged: every dot
+    // of `other` which `self` has seen is still alive in `self`, with a lesser
+    // value. A dot with a bottom value is the same as a missing one.
+    fn below(&self, other: &Self) -> bool {
+        self.context <= other.context
+            && other.entries.iter().all(|(k, dots)| {
+                let mine = self.entries.entry(k);
+                dots.iter().all(|(dot, v)| {
+                    v.is_bottom()
+                        || !self.context.contains(dot)
+                        || mine
+                            .and_then(|m| m.entry(dot))
+                            .is_some_and(|m| !m.is_bottom() && m.leq(v))
+                })
+            })
+    }
+}
+
+impl<A, K, V> Default for OrMap<A, K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+            context: Default::default(),
+        }
+    }
+}
+
+// Deriving `PartialEq` would not require `A: Ord`, which comparing the dots
+// of the entries needs.
+impl<A, K, V> PartialEq for OrMap<A, K, V>
+where
+    A: Ord,
+    K: PartialEq,
+    V: Semilattice,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries && self.context == other.context
+    }
+}
+
+impl<A, K, V> PartialOrd for OrMap<A, K, V>
+where
+    A: Ord,
+    K: Ord,
+    V: Semilattice,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self.below(other), other.below(self)) {
+            (true, true) => Some(cmp::Ordering::Equal),
+            (true, false) => Some(cmp::Ordering::Less),
+            (false, true) => Some(cmp::Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+impl<A, K, V> Semilattice for OrMap<A, K, V>
+where
+    A: Ord,
+    K: Ord,
+    V: Semilattice,
+{
+    fn join(self, other: Self) -> Self {
+        let (mut entries, mut others) = (self.entries.inner, other.entries.inner);
+        let (context, other_context) = (&self.context, &other.context);
+
+        // a dot survives if both sides have it, or if the other side has not
+        // seen it yet. A dot with a bottom value is the same as a missing one.
+        // The dots which both sides have are kept in `entries`, so the other
+        // side is checked against it afterwards.
+        retain_by_key(&mut entries, &others, |a, b| {
+            a.retain(|(dot, v)| {
+                !v.is_bottom()
+                    && (!other_context.contains(dot)
+                        || b.and_then(|b| b.entry(dot)).is_some_and(|v| !v.is_bottom()))
+            });
+            !a.is_empty()
+        });
+        retain_by_key(&mut others, &entries, |b, a| {
+            b.retain(|(dot, v)| {
+                !v.is_bottom()
+                    && (!context.contains(dot) || a.is_some_and(|a| a.entry(dot).is_some()))
+            });
+            !b.is_empty()
+        });
+        merge(&mut entries, others, |_| ());
+
+        Self {
+            entries: MapLattice { inner: entries },
+            context: self.context.join(other.context),
+        }
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        self.below(other)
+    }
+}
+
+impl<A, K, V> JoinRef for OrMap<A, K, V>
+where
+    A: Ord + Clone,
+    K: Ord + Clone,
+    V: Semilattice + Clone,
+{
+}
+
+/// An observed-remove set, where a concurrent insert wins over a remove. Like
+/// an `OrMap`, but only the dots of each element are kept, as there is no
+/// value to write.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(serialize = "A: Ord + serde::Serialize, T: serde::Serialize"))
+)]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
+pub struct OrSet<A, T> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    #[cfg_attr(feature = "minicbor", cbor(encode_bound = "A: Ord + minicbor::Encode"))]
+    pub entries: MapLattice<T, SetLattice<Dot<A>>>,
+    #[cfg_attr(feature = "minicbor", n(1))]
+    #[cfg_attr(feature = "minicbor", cbor(encode_bound = "A: Ord + minicbor::Encode"))]
+    pub context: VersionVector<A>,
+}
+
+impl<A, T> OrSet<A, T>
+where
+    A: Ord + Clone,
+    T: Ord,
+{
+    pub fn insert(&mut self, actor: A, val: T) {
+        let dot = self.context.increment(actor);
+
+        match self.entries.binary_search_by(|(v, _)| v.cmp(&val)) {
+            Ok(i) => self.entries.inner[i].1 = SetLattice::singleton(dot),
+            Err(i) => self
+                .entries
+                .inner
+                .insert(i, (val, SetLattice::singleton(dot))),
+        }
+    }
+
+    pub fn remove<Q>(&mut self, val: &Q)
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        if let Ok(i) = self.entries.binary_search_by(|(v, _)| v.borrow().cmp(val)) {
+            self.entries.inner.remove(i);
+        }
+    }
+
+    pub fn contains<Q>(&self, val: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.entries.entry(val).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|(v, _)| v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<A, T> OrSet<A, T>
+where
+    A: Ord,
+    T: Ord,
+{
+    // Whether joining `self` into `other` would leave it unchanged, as for an
+    // `OrMap`.
+    fn below(&self, other: &Self) -> bool {
+        self.context <= other.context
+            && other.entries.iter().all(|(v, dots)| {
+                let mine = self.entries.entry(v);
+                dots.iter()
+                    .all(|dot| !self.context.contains(dot) || mine.is_some_and(|m| m.contains(dot)))
+            })
+    }
+}
+
+impl<A, T> Default for OrSet<A, T> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+            context: Default::default(),
+        }
+    }
+}
+
+// Deriving `PartialEq` would not require `A: Ord`, which comparing the dots
+// of the entries needs.
+impl<A, T> PartialEq for OrSet<A, T>
+where
+    A: Ord,
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries && self.context == other.context
+    }
+}
+
+impl<A, T> PartialOrd for OrSet<A, T>
+where
+    A: Ord,
+    T: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self.below(other), other.below(self)) {
+            (true, true) => Some(cmp::Ordering::Equal),
+            (true, false) => Some(cmp::Ordering::Less),
+            (false, true) => Some(cmp::Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+impl<A, T> Semilattice for OrSet<A, T>
+where
+    A: Ord,
+    T: Ord,
+{
+    fn join(self, other: Self) -> Self {
+        let (mut entries, mut others) = (self.entries.inner, other.entries.inner);
+        let (context, other_context) = (&self.context, &other.context);
+
+        // a dot survives if both sides have it, or if the other side has not
+        // seen it yet, as for an `OrMap`.
+        retain_by_key(&mut entries, &others, |a, b| {
+            a.retain(|dot| !other_context.contains(dot) || b.is_some_and(|b| b.contains(dot)));
+            !a.is_empty()
+        });
+        retain_by_key(&mut others, &entries, |b, a| {
+            b.retain(|dot| !context.contains(dot) || a.is_some_and(|a| a.contains(dot)));
+            !b.is_empty()
+        });
+        merge(&mut entries, others, |_| ());
+
+        Self {
+            entries: MapLattice { inner: entries },
+            context: self.context.join(other.context),
+        }
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        self.below(other)
+    }
+}
+
+impl<A, T> JoinRef for OrSet<A, T>
+where
+    A: Ord + Clone,
+    T: Ord + Clone,
+{
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_semilattice_laws, Max};
+
+    let mut a = OrSet::default();
+    a.insert("alice", "bug");
+    a.insert("alice", "feature");
+
+    // bob removes a tag which alice concurrently re-adds.
+    let mut b = a.clone();
+    b.remove("bug");
+    let mut c = a.clone();
+    c.insert("alice", "bug");
+    c.remove("feature");
+
+    let d = b.clone().join(c.clone());
+    assert!(d.contains("bug") && !d.contains("feature"));
+    assert_eq!(d.iter().collect::<Vec<_>>(), [&"bug"]);
+
+    // a removed element can be added again.
+    let mut e = d.clone();
+    e.insert("bob", "feature");
+    assert_eq!(e.len(), 2);
+    assert_eq!(e.clone().join(b.clone()), e);
+
+    partially_verify_semilattice_laws([a, b, c, d, e]);
+
+    let mut a = OrMap::default();
+    a.insert("alice", "votes", Max(1));
+
+    let mut b = a.clone();
+    b.insert("bob", "votes", Max(3));
+    let mut c = a.clone();
+    c.remove("votes");
+
+    // the concurrent write survives the removal, without the removed value.
+    let d = b.clone().join(c.clone());
+    assert_eq!(d.get("votes"), Some(Max(3)));
+
+    let mut e = c.clone();
+    e.insert("carol", "votes", Max(2));
+    let f = d.clone().join(e.clone());
+    assert_eq!(f.get("votes"), Some(Max(3)));
+    assert_eq!(f.iter().count(), 1);
+
+    partially_verify_semilattice_laws([a, b, c, d, e, f]);
+}
@@ -1,6 +1,6 @@
 use core::{cmp, ops};
 
-use crate::{partial_ord_helper, Semilattice};
+use crate::{partial_ord_helper, BoundedLattice, Decompose, JoinRef, Lattice, Semilattice};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -39,6 +39,37 @@ where
     }
 }
 
+impl<T> JoinRef for Max<T> where T: num_traits::bounds::Bounded + Ord + Clone {}
+
+impl<T> Lattice for Max<T>
+where
+    T: num_traits::bounds::Bounded + Ord,
+{
+    fn meet(self, other: Self) -> Self {
+        Self(self.0.min(other.0))
+    }
+}
+
+impl<T> BoundedLattice for Max<T>
+where
+    T: num_traits::bounds::Bounded + Ord,
+{
+    fn top() -> Self {
+        Self(T::max_value())
+    }
+}
+
+impl<T> Decompose for Max<T>
+where
+    T: num_traits::bounds::Bounded + Ord,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if self != Self::default() {
+            func(self)
+        }
+    }
+}
+
 #[allow(clippy::derive_ord_xor_partial_ord)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -86,24 +117,268 @@ where
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Interval<T> {
-    pub lower: Max<T>,
-    pub upper: Min<T>,
+impl<T> JoinRef for Min<T> where T: num_traits::bounds::Bounded + Ord + Clone {}
+
+impl<T> Lattice for Min<T>
+where
+    T: num_traits::bounds::Bounded + Ord,
+{
+    fn meet(self, other: Self) -> Self {
+        Self(self.0.max(other.0))
+    }
 }
 
-impl<T> Interval<T>
+impl<T> BoundedLattice for Min<T>
 where
-    T: num_traits::bounds::Bounded,
+    T: num_traits::bounds::Bounded + Ord,
 {
     fn top() -> Self {
-        Self {
-            lower: Max(T::max_value()),
-            upper: Min(T::min_value()),
+        Self(T::min_value())
+    }
+}
+
+impl<T> Decompose for Min<T>
+where
+    T: num_traits::bounds::Bounded + Ord,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if self != Self::default() {
+            func(self)
+        }
+    }
+}
+
+/// The greatest value seen, for any totally ordered `T`. Unlike `Max`, the
+/// bottom element is explicit, so `T` need not have a least value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct Maximum<T> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub inner: Option<T>,
+}
+
+impl<T> Maximum<T> {
+    pub fn new(t: T) -> Self {
+        Self { inner: Some(t) }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        self.inner.as_ref()
+    }
+}
+
+impl<T> Default for Maximum<T> {
+    fn default() -> Self {
+        Self { inner: None }
+    }
+}
+
+impl<T> From<T> for Maximum<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T> Semilattice for Maximum<T>
+where
+    T: Ord,
+{
+    fn join(self, other: Self) -> Self {
+        self.max(other)
+    }
+}
+
+impl<T> JoinRef for Maximum<T> where T: Ord + Clone {}
+
+impl<T> Lattice for Maximum<T>
+where
+    T: Ord,
+{
+    fn meet(self, other: Self) -> Self {
+        self.min(other)
+    }
+}
+
+impl<T> Decompose for Maximum<T>
+where
+    T: Ord,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if self.inner.is_some() {
+            func(self)
+        }
+    }
+}
+
+/// The least value seen, for any totally ordered `T`. Unlike `Min`, the
+/// bottom element is explicit, so `T` need not have a greatest value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct Minimum<T> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub inner: Option<T>,
+}
+
+impl<T> Minimum<T> {
+    pub fn new(t: T) -> Self {
+        Self { inner: Some(t) }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        self.inner.as_ref()
+    }
+}
+
+impl<T> Default for Minimum<T> {
+    fn default() -> Self {
+        Self { inner: None }
+    }
+}
+
+impl<T> From<T> for Minimum<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T> cmp::PartialOrd for Minimum<T>
+where
+    T: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> cmp::Ord for Minimum<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        match (&self.inner, &other.inner) {
+            (Some(a), Some(b)) => b.cmp(a),
+            (a, b) => a.is_some().cmp(&b.is_some()),
         }
     }
 }
 
+impl<T> Semilattice for Minimum<T>
+where
+    T: Ord,
+{
+    fn join(self, other: Self) -> Self {
+        self.max(other)
+    }
+}
+
+impl<T> JoinRef for Minimum<T> where T: Ord + Clone {}
+
+impl<T> Lattice for Minimum<T>
+where
+    T: Ord,
+{
+    fn meet(self, other: Self) -> Self {
+        self.min(other)
+    }
+}
+
+impl<T> Decompose for Minimum<T>
+where
+    T: Ord,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if self.inner.is_some() {
+            func(self)
+        }
+    }
+}
+
+/// A float with the total order of the IEEE 754 `totalOrder` predicate, so
+/// that it can be used with `Max`, `Maximum` and the like. Negative NaNs are
+/// the least values and positive NaNs the greatest, and `-0.0 < +0.0`.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct TotalFloat<F>(#[cfg_attr(feature = "minicbor", n(0))] pub F);
+
+impl<F> ops::Deref for TotalFloat<F> {
+    type Target = F;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<F> From<F> for TotalFloat<F> {
+    fn from(f: F) -> Self {
+        Self(f)
+    }
+}
+
+macro_rules! total_float_impls {
+    ($($float:ident $bits:ident)+) => {$(
+        impl cmp::PartialEq for TotalFloat<$float> {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == cmp::Ordering::Equal
+            }
+        }
+
+        impl cmp::Eq for TotalFloat<$float> {}
+
+        impl cmp::PartialOrd for TotalFloat<$float> {
+            fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl cmp::Ord for TotalFloat<$float> {
+            fn cmp(&self, other: &Self) -> cmp::Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl num_traits::bounds::Bounded for TotalFloat<$float> {
+            fn min_value() -> Self {
+                // a negative NaN with every bit set.
+                Self($float::from_bits($bits::MAX))
+            }
+
+            fn max_value() -> Self {
+                // a positive NaN with every other bit set.
+                Self($float::from_bits($bits::MAX >> 1))
+            }
+        }
+    )+};
+}
+
+total_float_impls! {
+    f32 u32
+    f64 u64
+}
+
+/// An interval of possible values. The bottom element is the unknown, widest
+/// interval, and the top element is the contradiction of an empty interval.
+/// Any crossing bounds are that contradiction, and compare equal to `top()`.
+#[derive(Clone, Copy, Debug)]
+pub struct Interval<T> {
+    pub lower: Max<T>,
+    pub upper: Min<T>,
+}
+
 impl<T> Default for Interval<T>
 where
     T: num_traits::bounds::Bounded,
@@ -132,15 +407,35 @@ where
     }
 }
 
+// Deriving `PartialEq` would tell apart the many crossing intervals, which
+// are all the same empty interval.
+impl<T> PartialEq for Interval<T>
+where
+    T: num_traits::bounds::Bounded + Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        match (self.is_top(), other.is_top()) {
+            (true, true) => true,
+            (false, false) => self.lower == other.lower && self.upper == other.upper,
+            _ => false,
+        }
+    }
+}
+
 impl<T> cmp::PartialOrd for Interval<T>
 where
     T: num_traits::bounds::Bounded + Ord,
 {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        partial_ord_helper([
-            self.lower.partial_cmp(&other.lower),
-            self.upper.partial_cmp(&other.upper),
-        ])
+        match (self.is_top(), other.is_top()) {
+            (true, true) => Some(cmp::Ordering::Equal),
+            (true, false) => Some(cmp::Ordering::Greater),
+            (false, true) => Some(cmp::Ordering::Less),
+            (false, false) => partial_ord_helper([
+                self.lower.partial_cmp(&other.lower),
+                self.upper.partial_cmp(&other.upper),
+            ]),
+        }
     }
 }
 
@@ -159,4 +454,130 @@ where
             Self::top()
         }
     }
+
+    fn delta(self, since: &Self) -> Self {
+        // Each bound is only sent if it tightens the interval.
+        Self {
+            lower: self.lower.delta(&since.lower),
+            upper: self.upper.delta(&since.upper),
+        }
+    }
+}
+
+impl<T> JoinRef for Interval<T> where T: num_traits::bounds::Bounded + Ord + Clone {}
+
+impl<T> Lattice for Interval<T>
+where
+    T: num_traits::bounds::Bounded + Ord,
+{
+    /// The join is the intersection of two intervals, so the meet is their
+    /// hull. The lattice is not distributive.
+    fn meet(self, other: Self) -> Self {
+        if self.is_top() {
+            other
+        } else if other.is_top() {
+            self
+        } else {
+            Self {
+                lower: self.lower.meet(other.lower),
+                upper: self.upper.meet(other.upper),
+            }
+        }
+    }
+}
+
+impl<T> BoundedLattice for Interval<T>
+where
+    T: num_traits::bounds::Bounded + Ord,
+{
+    fn top() -> Self {
+        Self {
+            lower: BoundedLattice::top(),
+            upper: BoundedLattice::top(),
+        }
+    }
+
+    fn is_top(&self) -> bool {
+        // any crossing bounds are a contradiction.
+        self.lower.0 > self.upper.0
+    }
+}
+
+impl<T> Decompose for Interval<T>
+where
+    T: num_traits::bounds::Bounded + Ord,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        // each bound on its own; the top element is two crossing bounds.
+        self.lower.decompose(|lower| {
+            func(Self {
+                lower,
+                upper: Default::default(),
+            })
+        });
+        self.upper.decompose(|upper| {
+            func(Self {
+                lower: Default::default(),
+                upper,
+            })
+        });
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{
+        fold, partially_verify_decomposition, partially_verify_distributive_lattice_laws,
+        partially_verify_lattice_laws,
+    };
+
+    let intervals = [(0, 10), (5, 20), (-5, 3), (7, 7), (10, 0)].map(Interval::from);
+
+    assert_eq!(intervals[0].join(intervals[1]), Interval::from((5, 10)));
+    assert_eq!(intervals[1].join(intervals[2]), Interval::top());
+    assert!(intervals[4].is_top());
+    let crossing = Interval {
+        lower: Max(3),
+        upper: Min(1),
+    };
+    assert_eq!(crossing, Interval::top());
+    assert!(intervals[0] < crossing && intervals[4] <= crossing);
+    assert!(!intervals[3].is_top());
+    assert!(!Interval::<i32>::default().is_top());
+    assert!(Max::<i32>::top().is_top() && Min::<i32>::top().is_top());
+    assert_eq!(Max(3).join(Max::top()), Max::top());
+    assert_eq!(Min(3).join(Min::top()), Min::top());
+
+    assert_eq!(intervals[0].meet(intervals[2]), Interval::from((-5, 10)));
+    assert_eq!(intervals[4].meet(intervals[3]), intervals[3]);
+
+    partially_verify_lattice_laws(intervals);
+    partially_verify_distributive_lattice_laws((-3..3).map(Max));
+    partially_verify_distributive_lattice_laws((-3..3).map(Min));
+
+    partially_verify_decomposition(intervals);
+    partially_verify_decomposition((-3..3).map(Max));
+    partially_verify_decomposition((-3..3).map(Min));
+
+    let names = ["alice", "bob", "carol"];
+    assert_eq!(
+        fold(names.map(|name| Maximum::new((3, name)))),
+        Maximum::new((3, "carol"))
+    );
+    assert_eq!(fold(names.map(Minimum::new)), Minimum::new("alice"));
+    assert!(Minimum::default() < Minimum::new("carol"));
+
+    partially_verify_distributive_lattice_laws(names.map(Maximum::new));
+    partially_verify_distributive_lattice_laws(names.map(Minimum::new));
+    partially_verify_decomposition(names.map(Maximum::new));
+    partially_verify_decomposition(names.map(Minimum::new));
+
+    let floats = [f64::NAN, -f64::NAN, -0.0, 0.0, 1.5, f64::NEG_INFINITY].map(TotalFloat);
+    assert_eq!(fold(floats.map(Max)), Max(TotalFloat(f64::NAN)));
+    assert_eq!(fold(floats.map(Min)), Min(TotalFloat(-f64::NAN)));
+    assert!(TotalFloat(-0.0) < TotalFloat(0.0));
+    assert_eq!(Max::<TotalFloat<f32>>::top().0 .0.to_bits(), 0x7fff_ffff);
+
+    partially_verify_distributive_lattice_laws(floats.map(Max));
+    partially_verify_distributive_lattice_laws(floats.map(Maximum::new));
 }
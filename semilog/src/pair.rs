@@ -1,7 +1,9 @@
-use crate::{self as semilog, Semilattice};
+use crate::{self as semilog, BoundedLattice, Decompose, JoinRef, Lattice, Semilattice};
 
 /// An anonymous pair of semilattices.
-#[derive(Clone, Copy, Default, Debug, PartialEq, Semilattice)]
+#[derive(
+    Clone, Copy, Default, Debug, PartialEq, Semilattice, JoinRef, Decompose, BoundedLattice, Lattice,
+)]
 #[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
 pub struct Pair<A, B>(
     #[cfg_attr(feature = "minicbor", n(0))] pub A,
@@ -30,14 +32,24 @@ macro_rules! hlist {
 
 #[test]
 fn check_laws() {
-    use crate::{fold, partially_verify_semilattice_laws, Max, Min};
+    use crate::{
+        fold, partially_verify_decomposition, partially_verify_distributive_lattice_laws,
+        BoundedLattice, Max, Min,
+    };
 
     let _: HList!(u8, u16, u32) = hlist!(0u8, 1u16, 2u32);
 
-    partially_verify_semilattice_laws((-5..5).map(|x| Pair(Min(x), Max(x))));
+    partially_verify_distributive_lattice_laws((-5..5).map(|x| Pair(Min(x), Max(x))));
+    partially_verify_decomposition((-5..5).map(|x| Pair(Min(x), Max(x))));
 
     assert_eq!(
         fold((-5..5).map(|x| Pair(Min(x), Max(x)))),
         Pair(Min(-5), Max(4))
     );
+
+    assert_eq!(Pair::<Min<i8>, Max<i8>>::top(), Pair(Min(-128), Max(127)));
+    assert!(!Pair(Min(-128i8), Max(0i8)).is_top());
+    assert!(Pair(Min(-128i8), Max(0i8))
+        .join(Pair(Min(0), Max(127)))
+        .is_top());
 }
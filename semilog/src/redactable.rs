@@ -1,67 +1,145 @@
-use core::cmp::{Ordering, PartialEq, PartialOrd};
+use crate::{
+    self as semilog, BoundedLattice, ConflictMetadata, Decompose, Flat, JoinRef, Semilattice,
+};
 
-use crate::Semilattice;
-
-/// Redactable data. The contained data is arbitrary, not a semilattice. Any
-/// attempts to change the underlying value, will collapse to the redacted
-/// state.
-#[derive(Debug, Clone, PartialEq)]
+/// Redactable data, as a `Flat` lattice. The contained data is arbitrary, not
+/// a semilattice. Any attempts to change the underlying value, will collapse
+/// to the redacted state, which may record who redacted it and why.
+#[derive(Debug, Clone, Semilattice, JoinRef, BoundedLattice, Decompose)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
-pub enum Redactable<T> {
-    // FIXME: It is syntactically invalid to use this variant.
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct Redactable<T, M = ()> {
     #[cfg_attr(feature = "minicbor", n(0))]
-    Uninitialized,
-    #[cfg_attr(feature = "minicbor", n(1))]
-    Data(#[cfg_attr(feature = "minicbor", n(0))] T),
-    #[cfg_attr(feature = "minicbor", n(2))]
-    Redacted,
-}
-
-impl<T> Default for Redactable<T> {
-    fn default() -> Self {
-        Self::Uninitialized
-    }
+    pub inner: Flat<T, M>,
 }
 
-impl<T> PartialOrd for Redactable<T>
+impl<T, M> Redactable<T, M>
 where
     T: PartialEq,
+    M: ConflictMetadata<T>,
 {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        use Redactable::{Data, Redacted, Uninitialized};
-
-        match (self, other) {
-            (a, b) if a == b => Some(Ordering::Equal),
-            (_, Uninitialized) => Some(Ordering::Greater),
-            (Uninitialized, _) => Some(Ordering::Less),
-            (Redacted, Data(_)) => Some(Ordering::Greater),
-            (Data(_), Redacted) => Some(Ordering::Less),
-            _ => None,
+    pub fn new(data: T) -> Self {
+        Self {
+            inner: Flat::Known(data),
+        }
+    }
+
+    pub fn redacted() -> Self {
+        Self {
+            inner: Flat::Conflict(None),
+        }
+    }
+
+    pub fn redact(&mut self) {
+        self.join_assign(Self::redacted());
+    }
+
+    /// Redact the data, recording `metadata` such as who redacted it and why.
+    pub fn redact_with(&mut self, metadata: M) {
+        self.join_assign(Self {
+            inner: Flat::conflict(metadata),
+        });
+    }
+
+    /// The data, unless it has been redacted.
+    pub fn data(&self) -> Option<&T> {
+        self.inner.get()
+    }
+
+    pub fn is_redacted(&self) -> bool {
+        self.inner.is_conflict()
+    }
+
+    pub fn metadata(&self) -> Option<&M> {
+        self.inner.metadata()
+    }
+}
+
+impl<T, M> Default for Redactable<T, M> {
+    fn default() -> Self {
+        Self {
+            inner: Flat::Bottom,
         }
     }
 }
 
-impl<T> Semilattice for Redactable<T>
+// Deriving `PartialEq` would require `T` and `M` to be comparable themselves,
+// rather than only the `Flat` which the derived semilattice is bounded by.
+impl<T, M> PartialEq for Redactable<T, M>
 where
-    T: PartialEq,
+    Flat<T, M>: PartialEq,
 {
-    fn join(self, other: Self) -> Self {
-        use Redactable::{Data, Redacted, Uninitialized};
-
-        match (self, other) {
-            (Uninitialized, Data(a)) | (Data(a), Uninitialized) => Data(a),
-            (a, b) if a == b => a,
-            _ => Redacted,
-        }
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
     }
 }
 
 #[test]
 fn check_laws() {
-    use crate::partially_verify_semilattice_laws;
+    use crate::{partially_verify_decomposition, partially_verify_semilattice_laws};
+
+    let samples: [Redactable<_>; 3] = [
+        Redactable::redacted(),
+        Redactable::new("Hello world."),
+        Redactable::new("Hello kitty."),
+    ];
+
+    assert!(Redactable::<&str>::top().is_top());
+    assert!(samples[0].is_top() && !samples[1].is_top());
+    assert!(samples[1].clone().join(samples[2].clone()).is_top());
+
+    partially_verify_semilattice_laws(samples.clone());
+    partially_verify_decomposition(samples);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_metadata() {
+    use crate::{self as semilog, partially_verify_semilattice_laws, SetLattice};
+
+    #[derive(Clone, Debug, Default, PartialEq, semilog::Semilattice)]
+    struct Audit {
+        redacted_by: SetLattice<&'static str>,
+        reasons: SetLattice<&'static str>,
+        conflicts: SetLattice<&'static str>,
+    }
+
+    impl ConflictMetadata<&'static str> for Audit {
+        fn conflicting(value: &&'static str) -> Self {
+            Self {
+                conflicts: SetLattice::singleton(*value),
+                ..Default::default()
+            }
+        }
+    }
+
+    let a = Redactable::<_, Audit>::new("Hello world.");
+
+    let mut b = a.clone();
+    b.redact_with(Audit {
+        redacted_by: SetLattice::singleton("alice"),
+        reasons: SetLattice::singleton("typo"),
+        ..Default::default()
+    });
+    assert!(b.is_redacted() && b.data().is_none());
+
+    // a concurrent edit conflicts, and the value it replaced is recorded.
+    let c = Redactable::new("Hello kitty.");
+    let d = b.clone().join(c.clone());
+    let audit = d.metadata().expect("Expected metadata");
+    assert_eq!(audit.redacted_by, SetLattice::singleton("alice"));
+    assert_eq!(
+        audit.conflicts,
+        SetLattice::from_iter(["Hello kitty.", "Hello world."])
+    );
 
-    use Redactable::{Data, Redacted};
+    let mut e = Redactable::default();
+    e.redact();
+    assert_eq!(e, Redactable::redacted());
 
-    partially_verify_semilattice_laws([Redacted, Data("Hello world."), Data("Hello kitty.")]);
+    partially_verify_semilattice_laws([a, b, c, d, e]);
 }
@@ -0,0 +1,360 @@
+use core::{cmp, fmt, ops};
+
+use alloc::vec::Vec;
+
+use crate::{Decompose, JoinRef, MapLattice, Redactable, Semilattice};
+
+/// The position of an element of a `Sequence`. Positions are ordered
+/// lexicographically, and a new position can always be made between any two.
+/// The actor which made a position is part of its last component, so that
+/// concurrent insertions never collide.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct Position<A> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub inner: Vec<(u64, A)>,
+}
+
+impl<A> Position<A>
+where
+    A: Ord + Clone,
+{
+    /// A new position strictly between `lower` and `upper`, which default to
+    /// the start and end of the sequence.
+    ///
+    /// Every position made this way ends with a non-zero digit, and so has
+    /// room below it. Returns `None` if `upper` is not above `lower`, or if
+    /// it ends with a zero digit which leaves no room for `actor`.
+    pub fn between(lower: Option<&Self>, upper: Option<&Self>, actor: &A) -> Option<Self> {
+        let lower = lower.map_or(&[][..], |p| &p.inner[..]);
+        let upper = match upper {
+            Some(upper) => &upper.inner[..],
+            None => return Some(Self::after(lower, 0, actor)),
+        };
+
+        // the length of the common prefix of both bounds.
+        let level = lower
+            .iter()
+            .zip(upper)
+            .take_while(|(lo, hi)| lo == hi)
+            .count();
+
+        match (lower.get(level), upper.get(level)) {
+            (_, None) => None,
+            (Some(lo), Some(hi)) if lo > hi => None,
+            // there is room between the digits, or else anything after
+            // `lower` which keeps its digit here is below `upper`.
+            (Some((lo, _)), Some((hi, _))) if hi - lo > 1 => {
+                let mut inner = lower[..level].to_vec();
+                inner.push((lo + 1, actor.clone()));
+                Some(Self { inner })
+            }
+            (Some(_), Some(_)) => Some(Self::after(lower, level + 1, actor)),
+            // `lower` is a prefix of `upper`, so a position below the rest of
+            // `upper` is above `lower`.
+            (None, Some(_)) => {
+                let level = level
+                    + upper[level..]
+                        .iter()
+                        .position(|(d, a)| *d > 0 || actor < a)?;
+                let mut inner = upper[..level].to_vec();
+                match upper[level] {
+                    (d, _) if d > 1 => inner.push((1, actor.clone())),
+                    // (0, actor) is less than (1, _) or (0, a) for a > actor.
+                    _ => inner.extend([(0, actor.clone()), (1, actor.clone())]),
+                }
+                Some(Self { inner })
+            }
+        }
+    }
+
+    // A position above `lower` which keeps its first `level` digits.
+    fn after(lower: &[(u64, A)], level: usize, actor: &A) -> Self {
+        let mut inner = lower[..level].to_vec();
+        match lower.get(level) {
+            Some((d, _)) if *d < u64::MAX => inner.push((d + 1, actor.clone())),
+            _ => {
+                inner = lower.to_vec();
+                inner.push((1, actor.clone()));
+            }
+        }
+
+        Self { inner }
+    }
+}
+
+/// A sequence which can be edited concurrently, such as the text of a
+/// message. Each element is stored at a unique `Position`, and removed
+/// elements are kept as redacted tombstones.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct Sequence<A, T> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    #[cfg_attr(
+        feature = "minicbor",
+        cbor(encode_bound = "T: PartialEq + minicbor::Encode")
+    )]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(serialize = "A: serde::Serialize, T: PartialEq + serde::Serialize"))
+    )]
+    pub inner: MapLattice<Position<A>, Redactable<T>>,
+}
+
+impl<A, T> Sequence<A, T>
+where
+    A: Ord + Clone,
+    T: PartialEq,
+{
+    /// Insert `items` as `actor` before the `pos`th element which has not been
+    /// deleted. A `pos` past the end is clamped to `len()`.
+    pub fn insert(&mut self, actor: A, pos: usize, items: impl IntoIterator<Item = T>) {
+        // insert after the previous visible element, and before its direct
+        // successor, which may be a tombstone.
+        let i = match pos.min(self.len()).checked_sub(1) {
+            Some(prev) => self.visible_index(prev).expect("BUG!") + 1,
+            None => 0,
+        };
+
+        // later items extend the position of the first, so that concurrent
+        // insertions at the same place are not interleaved.
+        let mut first = None;
+
+        for (k, item) in items.into_iter().enumerate() {
+            let position = match &first {
+                None => {
+                    let lower = i.checked_sub(1).map(|i| &self.inner[i].0);
+                    let upper = self.inner.get(i).map(|(p, _)| p);
+                    // a successor which was not made by `between` may leave
+                    // no room below it, so the items go after it instead.
+                    let position = Position::between(lower, upper, &actor)
+                        .or_else(|| Position::between(lower, None, &actor))
+                        .expect("BUG!");
+                    first.insert(position).clone()
+                }
+                Some(Position { inner }) => {
+                    let mut inner = inner.clone();
+                    inner.push((k as u64, actor.clone()));
+                    Position { inner }
+                }
+            };
+
+            self.inner.insert(position, Redactable::new(item));
+        }
+    }
+
+    /// Delete the elements which have not been deleted yet, within `range`.
+    pub fn delete(&mut self, range: impl ops::RangeBounds<usize>) {
+        let mut visible = 0;
+
+        for (_, item) in self.inner.iter_mut() {
+            if item.data().is_some() {
+                if range.contains(&visible) {
+                    item.redact();
+                }
+                visible += 1;
+            }
+        }
+    }
+
+    /// The elements which have not been deleted.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.inner.iter().filter_map(|(_, item)| item.data())
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    // The index within `inner` of the `pos`th visible element.
+    fn visible_index(&self, pos: usize) -> Option<usize> {
+        self.inner
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, item))| item.data().is_some())
+            .nth(pos)
+            .map(|(i, _)| i)
+    }
+}
+
+impl<A> fmt::Display for Sequence<A, char>
+where
+    A: Ord + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.iter().try_for_each(|c| fmt::Write::write_char(f, *c))
+    }
+}
+
+impl<A, T> Default for Sequence<A, T> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<A, T> PartialOrd for Sequence<A, T>
+where
+    A: Ord,
+    T: PartialEq,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.inner.partial_cmp(&other.inner)
+    }
+}
+
+impl<A, T> Semilattice for Sequence<A, T>
+where
+    A: Ord,
+    T: PartialEq,
+{
+    fn join(self, other: Self) -> Self {
+        Self {
+            inner: self.inner.join(other.inner),
+        }
+    }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        self.inner.join_assign_changed(other.inner)
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        self.inner.leq(&other.inner)
+    }
+
+    fn is_bottom(&self) -> bool {
+        self.inner.is_bottom()
+    }
+
+    fn delta(self, since: &Self) -> Self {
+        Self {
+            inner: self.inner.delta(&since.inner),
+        }
+    }
+}
+
+impl<A, T> JoinRef for Sequence<A, T>
+where
+    A: Ord + Clone,
+    T: PartialEq + Clone,
+{
+    fn join_assign_ref(&mut self, other: &Self) {
+        self.inner.join_assign_ref(&other.inner)
+    }
+}
+
+impl<A, T> Decompose for Sequence<A, T>
+where
+    A: Ord + Clone,
+    T: PartialEq,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        self.inner.decompose(|inner| func(Self { inner }))
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_decomposition, partially_verify_semilattice_laws};
+    use alloc::string::ToString;
+
+    let mut a = Sequence::default();
+    a.insert("alice", 0, "Test #3 failed.".chars());
+
+    // bob fixes the number while alice adds to the end.
+    let mut b = a.clone();
+    b.delete(6..7);
+    b.insert("bob", 6, "4".chars());
+    let mut c = a.clone();
+    c.insert("alice", 15, " Oops.".chars());
+    c.insert("alice", 0, "Ah! ".chars());
+
+    let d = b.clone().join(c.clone());
+    assert_eq!(b.to_string(), "Test #4 failed.");
+    assert_eq!(c.to_string(), "Ah! Test #3 failed. Oops.");
+    assert_eq!(d.to_string(), "Ah! Test #4 failed. Oops.");
+
+    // concurrent insertions at the same place are not interleaved.
+    let mut e = a.clone();
+    e.insert("bob", 5, "(x) ".chars());
+    let mut f = a.clone();
+    f.insert("alice", 5, "(y) ".chars());
+    let g = e.clone().join(f.clone());
+    assert_eq!(g.to_string(), "Test (y) (x) #3 failed.");
+    assert_eq!(g.len(), 23);
+
+    // tombstones are kept, so deleted text is not revived by a join.
+    let mut h = g.clone();
+    h.delete(..);
+    h.insert("bob", 0, "x".chars());
+    h.insert("bob", 0, "y".chars());
+    assert_eq!(h.clone().join(g.clone()).to_string(), "yx");
+    h.insert("bob", 10, "z".chars());
+    assert_eq!(h.to_string(), "yxz");
+
+    // positions are strictly between their bounds, or there is no room.
+    let p = |inner: &[(u64, &'static str)]| Position {
+        inner: inner.to_vec(),
+    };
+    let upper = p(&[(3, "bob"), (0, "bob")]);
+    for (lower, upper) in [
+        (None, Some(&upper)),
+        (Some(p(&[(3, "bob")])), Some(&p(&[(3, "bob"), (1, "bob")]))),
+        (Some(p(&[(3, "bob")])), Some(&p(&[(3, "carol")]))),
+        (
+            Some(p(&[(3, "bob"), (5, "alice")])),
+            Some(&p(&[(4, "bob")])),
+        ),
+        (Some(p(&[(3, "bob"), (u64::MAX, "bob")])), None),
+        (Some(p(&[(3, "bob")])), Some(&p(&[(9, "bob")]))),
+    ] {
+        for actor in ["alice", "carol"] {
+            let position = Position::between(lower.as_ref(), upper, &actor).unwrap();
+            assert!(lower.as_ref() < Some(&position));
+            assert!(upper.is_none_or(|upper| position < *upper));
+            assert_ne!(position.inner.last().unwrap().0, 0);
+        }
+    }
+    let lower = p(&[(3, "bob")]);
+    assert_eq!(
+        Position::between(Some(&lower), Some(&upper), &"carol"),
+        None
+    );
+    let position = Position::between(Some(&lower), Some(&upper), &"alice").unwrap();
+    assert!(lower < position && position < upper);
+    assert_eq!(
+        Position::between(Some(&upper), Some(&lower), &"alice"),
+        None
+    );
+    assert_eq!(
+        Position::between(Some(&lower), Some(&lower), &"alice"),
+        None
+    );
+
+    // an insertion before a position with no room below it goes after it.
+    let mut i = Sequence::default();
+    i.inner.insert(lower, Redactable::new('a'));
+    i.inner.insert(upper, Redactable::new('c'));
+    i.insert("carol", 1, "b".chars());
+    assert_eq!(i.to_string(), "acb");
+    i.insert("alice", 1, "b".chars());
+    assert_eq!(i.to_string(), "abcb");
+
+    partially_verify_semilattice_laws([a.clone(), b.clone(), c.clone(), d.clone(), g.clone()]);
+    partially_verify_decomposition([a, b, c, d, g, h]);
+}
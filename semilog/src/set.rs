@@ -1,17 +1,15 @@
-use core::{cmp, ops};
+use alloc::{vec, vec::Vec};
+use core::{borrow::Borrow, cmp, mem, ops};
 
-use crate::{DeferredRestore, Map, MapLattice, Semilattice};
+use crate::{
+    map::{gallop, merge_by, merge_by_ref},
+    Decompose, DeferredRestore, JoinRef, JoinReport, Lattice, Map, Semilattice,
+};
 
+/// A set, joined by union. The elements are kept sorted and unique.
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[cfg_attr(
-    feature = "minicbor",
-    derive(minicbor::Encode, minicbor::Decode),
-    cbor(transparent)
-)]
 pub struct SetLattice<V> {
-    #[cfg_attr(feature = "minicbor", n(0))]
-    pub inner: MapLattice<V, ()>,
+    pub inner: Vec<V>,
 }
 
 impl<V> SetLattice<V>
@@ -19,18 +17,44 @@ where
     V: Ord,
 {
     pub fn singleton(val: V) -> Self {
-        Self {
-            inner: MapLattice::singleton(val, ()),
-        }
+        Self { inner: vec![val] }
     }
 
     pub fn insert(&mut self, val: V) {
-        self.inner.insert(val, ());
+        if let Err(i) = self.inner.binary_search(&val) {
+            self.inner.insert(i, val);
+        }
+    }
+
+    pub fn contains<Q>(&self, val: &Q) -> bool
+    where
+        V: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.inner.binary_search_by(|v| v.borrow().cmp(val)).is_ok()
+    }
+
+    /// Join `other` into `self`, reporting each newly added element.
+    pub fn join_assign_report(&mut self, other: Self) -> JoinReport<V>
+    where
+        V: Clone,
+    {
+        let mut report = JoinReport::default();
+        merge(&mut self.inner, other.inner, |v| {
+            report.inflated.push(v.clone())
+        });
+        report
     }
 }
 
+// Merge the sorted elements of `other` into `vec`, like the entries of a
+// `MapLattice`.
+fn merge<V: Ord>(vec: &mut Vec<V>, other: Vec<V>, inflated: impl FnMut(&V)) {
+    merge_by(vec, other, |v| v, |_, _| false, inflated)
+}
+
 impl<V> ops::Deref for SetLattice<V> {
-    type Target = MapLattice<V, ()>;
+    type Target = Vec<V>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
@@ -43,28 +67,20 @@ impl<V> ops::DerefMut for SetLattice<V> {
     }
 }
 
-impl<V> Default for SetLattice<V>
-where
-    MapLattice<V, ()>: Default,
-{
+impl<V> Default for SetLattice<V> {
     fn default() -> Self {
         Self {
-            inner: Default::default(),
+            inner: Vec::default(),
         }
     }
 }
 
 impl<'a, V: 'a> IntoIterator for &'a SetLattice<V> {
     type Item = &'a V;
-
-    // Ohh. Don't need #![feature(type_alias_impl_trait)] for `impl
-    // Iterator<Item = Self::Item>` because the closure doesn't capture its
-    // environment, thus is also an ordinary `fn`.
-    #[allow(clippy::type_complexity)]
-    type IntoIter = core::iter::Map<core::slice::Iter<'a, (V, ())>, fn(&'a (V, ())) -> &'a V>;
+    type IntoIter = core::slice::Iter<'a, V>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.inner.iter().map(|(v, _)| v)
+        self.inner.iter()
     }
 }
 
@@ -73,9 +89,11 @@ where
     V: Ord,
 {
     fn from_iter<I: IntoIterator<Item = V>>(iter: I) -> Self {
-        Self {
-            inner: MapLattice::from_iter(iter.into_iter().map(|v| (v, ()))),
-        }
+        let mut inner = Vec::from_iter(iter);
+        inner.sort_unstable();
+        inner.dedup();
+
+        Self { inner }
     }
 }
 
@@ -84,7 +102,12 @@ where
     V: Ord,
 {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        self.inner.partial_cmp(&other.inner)
+        match (self.leq(other), other.leq(self)) {
+            (true, true) => Some(cmp::Ordering::Equal),
+            (true, false) => Some(cmp::Ordering::Less),
+            (false, true) => Some(cmp::Ordering::Greater),
+            (false, false) => None,
+        }
     }
 }
 
@@ -92,10 +115,126 @@ impl<V> Semilattice for SetLattice<V>
 where
     V: Ord,
 {
-    fn join(self, other: Self) -> Self {
-        Self {
-            inner: self.inner.join(other.inner),
+    fn join(mut self, mut other: Self) -> Self {
+        // merge the smaller set into the larger.
+        if self.inner.len() < other.inner.len() {
+            mem::swap(&mut self, &mut other);
+        }
+
+        merge(&mut self.inner, other.inner, |_| ());
+        self
+    }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        let mut changed = false;
+        merge(&mut self.inner, other.inner, |_| changed = true);
+        changed
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        // every element of `self` is in `other`, galloping over the elements
+        // which only `other` has.
+        let mut b = &other.inner[..];
+        self.inner.iter().all(|v| {
+            b = gallop(b, |v2| v2 < v);
+            b.first() == Some(v)
+        })
+    }
+
+    fn is_bottom(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn delta(mut self, since: &Self) -> Self {
+        self.inner.retain(|v| !since.contains(v));
+        self
+    }
+}
+
+impl<V> JoinRef for SetLattice<V>
+where
+    V: Ord + Clone,
+{
+    fn join_assign_ref(&mut self, other: &Self) {
+        // only the elements which are inserted are cloned.
+        merge_by_ref(&mut self.inner, &other.inner, |v| v, |_, _| ())
+    }
+}
+
+impl<V> Lattice for SetLattice<V>
+where
+    V: Ord,
+{
+    fn meet(mut self, other: Self) -> Self {
+        self.inner.retain(|v| other.contains(v));
+        self
+    }
+}
+
+impl<V> Decompose for SetLattice<V>
+where
+    V: Ord,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        for v in self.inner {
+            func(Self::singleton(v))
+        }
+    }
+}
+
+/// Encoded like a `MapLattice` of each element to `()`.
+#[cfg(feature = "minicbor")]
+impl<V> minicbor::Encode for SetLattice<V>
+where
+    V: minicbor::Encode,
+{
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(self.inner.len() as u64)?;
+        for v in &self.inner {
+            e.encode((v, ()))?;
         }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "minicbor")]
+impl<'b, V> minicbor::Decode<'b> for SetLattice<V>
+where
+    V: minicbor::Decode<'b>,
+{
+    fn decode(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        let inner = d.array_iter()?.map(|entry| entry.map(|(v, ())| v));
+
+        Ok(Self {
+            inner: inner.collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<V> serde::Serialize for SetLattice<V>
+where
+    V: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.inner.iter().map(|v| (v, ())))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V> serde::Deserialize<'de> for SetLattice<V>
+where
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = <Vec<(V, ())>>::deserialize(deserializer)?;
+
+        Ok(Self {
+            inner: entries.into_iter().map(|(v, ())| v).collect(),
+        })
     }
 }
 
@@ -144,7 +283,7 @@ where
 
 #[test]
 fn check_laws() {
-    use crate::partially_verify_semilattice_laws;
+    use crate::{partially_verify_decomposition, partially_verify_distributive_lattice_laws};
 
     let a = SetLattice::from_iter([("Alice", 123), ("Bob", 50)]);
     let b = SetLattice::from_iter([("Bob", 300), ("Carol", 100)]);
@@ -163,5 +302,34 @@ fn check_laws() {
         ])
     );
 
-    partially_verify_semilattice_laws([a, b, c, d]);
+    assert_eq!(d.clone().meet(c.clone()), c);
+
+    let mut e = a.clone();
+    assert_eq!(
+        e.join_assign_report(b.clone()).inflated,
+        [("Bob", 300), ("Carol", 100)]
+    );
+    assert!(!e.join_assign_changed(b.clone()));
+    assert!(e.contains(&("Carol", 100)) && !e.contains(&("Carol", 150)));
+
+    // encoded as pairs of each element and `()`.
+    #[cfg(feature = "minicbor")]
+    {
+        fn encode(x: impl minicbor::Encode) -> Vec<u8> {
+            let mut bytes = Vec::new();
+            minicbor::encode(x, &mut bytes).unwrap();
+            bytes
+        }
+
+        let bytes = encode(&d);
+        let pairs = d.iter().map(|v| (*v, ())).collect::<Vec<_>>();
+        assert_eq!(bytes, encode(pairs));
+        assert_eq!(
+            minicbor::decode::<SetLattice<(&str, i32)>>(&bytes).unwrap(),
+            d
+        );
+    }
+
+    partially_verify_distributive_lattice_laws([a.clone(), b.clone(), c.clone(), d.clone()]);
+    partially_verify_decomposition([a, b, c, d]);
 }
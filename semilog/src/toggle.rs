@@ -0,0 +1,241 @@
+use core::{cmp, fmt, hash, marker::PhantomData};
+
+use crate::{Decompose, JoinRef, Lattice, Semilattice};
+
+/// The states a `Toggle` cycles through, numbered from zero up to `N`.
+pub trait ToggleState: Copy {
+    /// The number of states, which must not be zero.
+    const N: usize;
+
+    /// The state numbered `index`, which is less than `N`.
+    fn from_index(index: usize) -> Self;
+
+    /// The number of this state, which must be less than `N`.
+    fn index(self) -> usize;
+}
+
+impl ToggleState for bool {
+    const N: usize = 2;
+
+    fn from_index(index: usize) -> Self {
+        index != 0
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// A value which cycles through the `N` states of `S`, ordered by the number
+/// of transitions made. The state is the number of transitions modulo `N`, and
+/// each transition only ever increases the count, so the latest state wins.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent, bound = ""))]
+pub struct Toggle<S> {
+    transitions: u64,
+    state: PhantomData<fn() -> S>,
+}
+
+/// The membership of an element which can be added and removed any number of
+/// times. Odd lengths are present, even lengths are absent.
+pub type CausalLength = Toggle<bool>;
+
+impl<S> Toggle<S> {
+    pub const fn new(transitions: u64) -> Self {
+        Self {
+            transitions,
+            state: PhantomData,
+        }
+    }
+
+    pub fn transitions(&self) -> u64 {
+        self.transitions
+    }
+}
+
+impl<S> Toggle<S>
+where
+    S: ToggleState,
+{
+    // Evaluated wherever a toggle changes state, so a state type without any
+    // states fails to compile rather than dividing by zero.
+    const STATES: u64 = {
+        assert!(S::N != 0, "A toggle needs at least one state");
+        S::N as u64
+    };
+
+    pub fn state(&self) -> S {
+        S::from_index((self.transitions % Self::STATES) as usize)
+    }
+
+    /// Make the fewest transitions which reach `state`.
+    pub fn set(&mut self, state: S) {
+        let current = self.transitions % Self::STATES;
+        let target = state.index() as u64 % Self::STATES;
+
+        self.transitions += (target + Self::STATES - current) % Self::STATES;
+    }
+}
+
+impl<S> From<S> for Toggle<S>
+where
+    S: ToggleState,
+{
+    fn from(state: S) -> Self {
+        let mut toggle = Self::default();
+        toggle.set(state);
+        toggle
+    }
+}
+
+impl CausalLength {
+    pub fn is_present(&self) -> bool {
+        self.state()
+    }
+
+    pub fn set_present(&mut self, present: bool) {
+        self.set(present)
+    }
+}
+
+// The state type is only a marker, so none of these depend on it.
+
+impl<S> Clone for Toggle<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for Toggle<S> {}
+
+impl<S> Default for Toggle<S> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<S> fmt::Debug for Toggle<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Toggle").field(&self.transitions).finish()
+    }
+}
+
+impl<S> PartialEq for Toggle<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.transitions == other.transitions
+    }
+}
+
+impl<S> Eq for Toggle<S> {}
+
+impl<S> PartialOrd for Toggle<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for Toggle<S> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.transitions.cmp(&other.transitions)
+    }
+}
+
+impl<S> hash::Hash for Toggle<S> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.transitions.hash(state)
+    }
+}
+
+impl<S> Semilattice for Toggle<S> {
+    fn join(self, other: Self) -> Self {
+        self.max(other)
+    }
+}
+
+impl<S> JoinRef for Toggle<S> {}
+
+impl<S> Lattice for Toggle<S> {
+    fn meet(self, other: Self) -> Self {
+        self.min(other)
+    }
+}
+
+impl<S> Decompose for Toggle<S> {
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        if self != Self::default() {
+            func(self)
+        }
+    }
+}
+
+// Encoded as an array of the transition count, as a derived tuple struct.
+#[cfg(feature = "minicbor")]
+impl<S> minicbor::Encode for Toggle<S> {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(1)?.u64(self.transitions)?.ok()
+    }
+}
+
+#[cfg(feature = "minicbor")]
+impl<'b, S> minicbor::Decode<'b> for Toggle<S> {
+    fn decode(d: &mut minicbor::Decoder<'b>) -> Result<Self, minicbor::decode::Error> {
+        if Some(1) != d.array()? {
+            return Err(minicbor::decode::Error::Message(
+                "expected a toggle, i.e. an array of one transition count",
+            ));
+        }
+
+        Ok(Self::new(d.u64()?))
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_decomposition, partially_verify_distributive_lattice_laws};
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Vote {
+        Neutral,
+        Up,
+        Down,
+        Invalid,
+    }
+
+    impl ToggleState for Vote {
+        const N: usize = 4;
+
+        fn from_index(index: usize) -> Self {
+            [Vote::Neutral, Vote::Up, Vote::Down, Vote::Invalid][index]
+        }
+
+        fn index(self) -> usize {
+            self as usize
+        }
+    }
+
+    let mut a = CausalLength::default();
+    a.set_present(true);
+    a.set_present(true);
+    assert_eq!(a, Toggle::new(1));
+
+    let mut b = a;
+    b.set_present(false);
+    let mut c = b;
+    c.set_present(true);
+    assert!(!b.is_present() && c.is_present());
+
+    // removing concurrently with a re-add loses to it.
+    assert!(b.join(c).is_present());
+
+    let mut d = Toggle::from(Vote::Down);
+    d.set(Vote::Up);
+    assert_eq!((d.state(), d.transitions()), (Vote::Up, 5));
+    d.set(Vote::Invalid);
+    assert_eq!((d.state(), d.transitions()), (Vote::Invalid, 7));
+
+    partially_verify_distributive_lattice_laws([Toggle::default(), a, b, c]);
+    partially_verify_decomposition([Toggle::default(), d, Toggle::new(2)]);
+}
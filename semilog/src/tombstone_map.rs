@@ -0,0 +1,192 @@
+use core::{borrow::Borrow, cmp};
+
+use crate::{Decompose, JoinRef, MapLattice, Semilattice, SetLattice};
+
+/// A map whose keys can be retired, after which they are never live again.
+/// Retired keys are recorded in a grow-only set, and their values are dropped.
+///
+/// No key of `live` may be in `retired`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
+pub struct TombstoneMap<K, V> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    #[cfg_attr(
+        feature = "minicbor",
+        cbor(encode_bound = "V: Semilattice + minicbor::Encode")
+    )]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(serialize = "V: Semilattice + serde::Serialize"))
+    )]
+    pub live: MapLattice<K, V>,
+    #[cfg_attr(feature = "minicbor", n(1))]
+    pub retired: SetLattice<K>,
+}
+
+impl<K, V> TombstoneMap<K, V>
+where
+    K: Ord,
+    V: Semilattice,
+{
+    /// Join `val` into the value of `key`, unless it has been retired.
+    pub fn insert(&mut self, key: K, val: V) {
+        if !self.is_retired(&key) {
+            self.live.insert(key, val);
+        }
+    }
+
+    /// Retire `key` permanently, dropping its value.
+    pub fn remove(&mut self, key: K) {
+        if let Ok(i) = self.live.binary_search_by(|(k, _)| k.cmp(&key)) {
+            self.live.remove(i);
+        }
+        self.retired.insert(key);
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.live.entry(key)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.live.entry(key).is_some()
+    }
+
+    pub fn is_retired<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.retired.contains(key)
+    }
+
+    /// The live entries.
+    pub fn iter(&self) -> impl Iterator<Item = &(K, V)> {
+        self.live.iter()
+    }
+
+    // Whether every live entry of `self` is either retired by `other`, or
+    // less than its entry in `other`, where a missing entry is bottom.
+    fn below(&self, other: &Self) -> bool {
+        self.retired <= other.retired
+            && self.live.iter().all(|(k, v)| {
+                other.is_retired(k) || other.live.entry(k).map_or(v.is_bottom(), |v2| v.leq(v2))
+            })
+    }
+}
+
+impl<K, V> Default for TombstoneMap<K, V> {
+    fn default() -> Self {
+        Self {
+            live: Default::default(),
+            retired: Default::default(),
+        }
+    }
+}
+
+// Deriving `PartialEq` would not require `V: Semilattice`, which comparing
+// the live entries needs.
+impl<K, V> PartialEq for TombstoneMap<K, V>
+where
+    K: PartialEq,
+    V: Semilattice,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.live == other.live && self.retired == other.retired
+    }
+}
+
+impl<K, V> PartialOrd for TombstoneMap<K, V>
+where
+    K: Ord,
+    V: Semilattice,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self.below(other), other.below(self)) {
+            (true, true) => Some(cmp::Ordering::Equal),
+            (true, false) => Some(cmp::Ordering::Less),
+            (false, true) => Some(cmp::Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+impl<K, V> Semilattice for TombstoneMap<K, V>
+where
+    K: Ord,
+    V: Semilattice,
+{
+    fn join(self, other: Self) -> Self {
+        let retired = self.retired.join(other.retired);
+        let mut live = self.live.join(other.live);
+        live.retain(|(k, _)| !retired.contains(k));
+
+        Self { live, retired }
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        self.below(other)
+    }
+}
+
+impl<K, V> JoinRef for TombstoneMap<K, V>
+where
+    K: Ord + Clone,
+    V: Semilattice + Clone,
+{
+}
+
+impl<K, V> Decompose for TombstoneMap<K, V>
+where
+    K: Ord + Clone,
+    V: Decompose,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        self.live.decompose(|live| {
+            func(Self {
+                live,
+                retired: Default::default(),
+            })
+        });
+        self.retired.decompose(|retired| {
+            func(Self {
+                live: Default::default(),
+                retired,
+            })
+        });
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_decomposition, partially_verify_semilattice_laws, Max};
+
+    let mut a = TombstoneMap::default();
+    a.insert("thread-0", Max(1));
+    a.insert("thread-1", Max(1));
+
+    let mut b = a.clone();
+    b.remove("thread-0");
+    b.insert("thread-0", Max(2));
+    let mut c = a.clone();
+    c.insert("thread-0", Max(3));
+    c.remove("thread-2");
+
+    let d = b.clone().join(c.clone());
+    assert!(a.contains_key("thread-0") && !d.contains_key("thread-0"));
+    assert!(d.is_retired("thread-2"));
+    assert_eq!(d.get("thread-1"), Some(&Max(1)));
+    assert_eq!(d.iter().count(), 1);
+    assert!(c < d);
+
+    partially_verify_semilattice_laws([a.clone(), b.clone(), c.clone(), d.clone()]);
+    partially_verify_decomposition([a, b, c, d]);
+}
@@ -0,0 +1,165 @@
+use core::{borrow::Borrow, cmp};
+
+use crate::{partial_ord_helper, Decompose, JoinRef, Lattice, Semilattice, SetLattice};
+
+/// A set whose elements can be removed once, and never added again. Removals
+/// are recorded in a second grow-only set, which always wins.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
+pub struct TwoPhaseSet<T> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub added: SetLattice<T>,
+    #[cfg_attr(feature = "minicbor", n(1))]
+    pub removed: SetLattice<T>,
+}
+
+impl<T> TwoPhaseSet<T>
+where
+    T: Ord,
+{
+    pub fn insert(&mut self, val: T) {
+        self.added.insert(val);
+    }
+
+    /// Remove `val` permanently, whether or not it has been added yet.
+    pub fn remove(&mut self, val: T) {
+        self.removed.insert(val);
+    }
+
+    pub fn contains<Q>(&self, val: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.added.contains(val) && !self.is_removed(val)
+    }
+
+    pub fn is_removed<Q>(&self, val: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.removed.contains(val)
+    }
+
+    /// The elements which have been added and not removed.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.added.into_iter().filter(|val| !self.is_removed(*val))
+    }
+}
+
+impl<T> Default for TwoPhaseSet<T> {
+    fn default() -> Self {
+        Self {
+            added: Default::default(),
+            removed: Default::default(),
+        }
+    }
+}
+
+impl<T> PartialOrd for TwoPhaseSet<T>
+where
+    T: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        partial_ord_helper([
+            self.added.partial_cmp(&other.added),
+            self.removed.partial_cmp(&other.removed),
+        ])
+    }
+}
+
+impl<T> Semilattice for TwoPhaseSet<T>
+where
+    T: Ord,
+{
+    fn join(self, other: Self) -> Self {
+        Self {
+            added: self.added.join(other.added),
+            removed: self.removed.join(other.removed),
+        }
+    }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        self.added.join_assign_changed(other.added)
+            | self.removed.join_assign_changed(other.removed)
+    }
+
+    fn delta(self, since: &Self) -> Self {
+        Self {
+            added: self.added.delta(&since.added),
+            removed: self.removed.delta(&since.removed),
+        }
+    }
+}
+
+impl<T> JoinRef for TwoPhaseSet<T>
+where
+    T: Ord + Clone,
+{
+    fn join_assign_ref(&mut self, other: &Self) {
+        self.added.join_assign_ref(&other.added);
+        self.removed.join_assign_ref(&other.removed);
+    }
+}
+
+impl<T> Lattice for TwoPhaseSet<T>
+where
+    T: Ord,
+{
+    fn meet(self, other: Self) -> Self {
+        Self {
+            added: self.added.meet(other.added),
+            removed: self.removed.meet(other.removed),
+        }
+    }
+}
+
+impl<T> Decompose for TwoPhaseSet<T>
+where
+    T: Ord,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        self.added.decompose(|added| {
+            func(Self {
+                added,
+                removed: Default::default(),
+            })
+        });
+        self.removed.decompose(|removed| {
+            func(Self {
+                added: Default::default(),
+                removed,
+            })
+        });
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_decomposition, partially_verify_lattice_laws};
+    use alloc::vec::Vec;
+
+    let mut a = TwoPhaseSet::default();
+    a.insert(("alice", 0));
+    a.insert(("alice", 1));
+
+    let mut b = a.clone();
+    b.remove(("alice", 0));
+    let mut c = a.clone();
+    c.insert(("bob", 0));
+    // closed before it was seen.
+    c.remove(("bob", 1));
+
+    let mut d = b.clone().join(c.clone());
+    d.insert(("alice", 0));
+    d.insert(("bob", 1));
+
+    assert!(a.contains(&("alice", 0)) && !d.contains(&("alice", 0)));
+    assert!(d.is_removed(&("bob", 1)));
+    assert_eq!(d.iter().collect::<Vec<_>>(), [&("alice", 1), &("bob", 0)]);
+
+    partially_verify_lattice_laws([a.clone(), b.clone(), c.clone(), d.clone()]);
+    partially_verify_decomposition([a, b, c, d]);
+}
@@ -3,17 +3,15 @@ use core::{
     ops,
 };
 
-use crate::{partial_ord_helper, Semilattice};
+use crate::{partial_ord_helper, Decompose, JoinRef, JoinReport, Semilattice};
 
 use alloc::{vec, vec::Vec};
 
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[cfg_attr(
-    feature = "minicbor",
-    derive(minicbor::Encode, minicbor::Decode),
-    cbor(transparent)
-)]
+/// A vector whose elements are joined by their index. Trailing bottom
+/// elements are the same as a shorter vector, for comparisons and encoding.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Decode), cbor(transparent))]
 pub struct VecLattice<T> {
     #[cfg_attr(feature = "minicbor", n(0))]
     pub inner: Vec<T>,
@@ -32,14 +30,11 @@ where
     T: Semilattice,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let (a, b) = (self.present(), other.present());
+
         partial_ord_helper([
-            self.inner.len().partial_cmp(&other.inner.len()),
-            partial_ord_helper(
-                self.inner
-                    .iter()
-                    .zip(&other.inner)
-                    .map(|(a, b)| a.partial_cmp(b)),
-            ),
+            a.len().partial_cmp(&b.len()),
+            partial_ord_helper(a.iter().zip(b).map(|(a, b)| a.partial_cmp(b))),
         ])
     }
 }
@@ -53,14 +48,80 @@ where
             Some(Ordering::Greater | Ordering::Equal) => self,
             Some(Ordering::Less) => other,
             None => {
-                for (l, r) in self.inner.iter_mut().zip(other.inner) {
+                let mut other = other.inner.into_iter();
+                for (l, r) in self.inner.iter_mut().zip(&mut other) {
                     l.join_assign(r);
                 }
+                // keep the tail of `other` if it was the longer of the two.
+                self.inner.extend(other);
+                self.normalize();
 
                 self
             }
         }
     }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        let mut changed = false;
+        self.merge_assign(other, |_| changed = true);
+        changed
+    }
+
+    fn leq(&self, other: &Self) -> bool {
+        // the last present element of `self` must not be past the end of
+        // `other`.
+        let (a, b) = (self.present(), other.present());
+        a.len() <= b.len() && a.iter().zip(b).all(|(l, r)| l.leq(r))
+    }
+
+    fn is_bottom(&self) -> bool {
+        self.present().is_empty()
+    }
+
+    fn delta(mut self, since: &Self) -> Self {
+        if self.leq(since) {
+            return Self::default();
+        }
+
+        for (l, r) in self.inner.iter_mut().zip(&since.inner) {
+            *l = core::mem::take(l).delta(r);
+        }
+
+        self.normalize();
+        self
+    }
+}
+
+impl<T> JoinRef for VecLattice<T>
+where
+    T: JoinRef,
+{
+    fn join_assign_ref(&mut self, other: &Self) {
+        let other = other.present();
+        for (l, r) in self.inner.iter_mut().zip(other) {
+            l.join_assign_ref(r);
+        }
+        // clone the tail of `other` if it is the longer of the two.
+        if let Some(tail) = other.get(self.inner.len()..) {
+            self.inner.extend_from_slice(tail);
+        }
+    }
+}
+
+impl<T> Decompose for VecLattice<T>
+where
+    T: Decompose,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        for (i, v) in self.inner.into_iter().enumerate() {
+            v.decompose(|part| {
+                let mut inner = Vec::with_capacity(i + 1);
+                inner.resize_with(i, T::default);
+                inner.push(part);
+                func(Self { inner })
+            });
+        }
+    }
 }
 
 impl<T> ops::Deref for VecLattice<T> {
@@ -87,6 +148,33 @@ where
         self.inner.get(key as usize)
     }
 
+    /// Join `other` into `self`, reporting each index whose element was
+    /// inflated or which was newly added.
+    pub fn join_assign_report(&mut self, other: Self) -> JoinReport<u64> {
+        let mut report = JoinReport::default();
+        self.merge_assign(other, |i| report.inflated.push(i));
+        report
+    }
+
+    fn merge_assign(&mut self, mut other: Self, mut inflated: impl FnMut(u64)) {
+        let len = self.inner.len();
+        other.normalize();
+        let mut other = other.inner.into_iter();
+
+        for (i, (l, r)) in self.inner.iter_mut().zip(&mut other).enumerate() {
+            if l.join_assign_changed(r) {
+                inflated(i as u64);
+            }
+        }
+
+        for (i, r) in other.enumerate() {
+            if !r.is_bottom() {
+                inflated((len + i) as u64);
+            }
+            self.inner.push(r);
+        }
+    }
+
     pub fn entry_mut(&mut self, key: u64) -> &mut T {
         if self.inner.len() <= key as usize {
             self.inner.resize_with(1 + key as usize, T::default);
@@ -94,4 +182,114 @@ where
 
         self.inner.get_mut(key as usize).expect("BUG!")
     }
+
+    /// Remove the trailing bottom elements, such as those left behind by
+    /// `entry_mut`, which are already treated as missing.
+    pub fn normalize(&mut self) {
+        let len = self.present().len();
+        self.inner.truncate(len);
+    }
+
+    // The elements up to the last one which is not bottom.
+    fn present(&self) -> &[T] {
+        let bottom = self.inner.iter().rev().take_while(|x| x.is_bottom());
+        &self.inner[..self.inner.len() - bottom.count()]
+    }
+}
+
+impl<T> PartialEq for VecLattice<T>
+where
+    T: Semilattice,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.present() == other.present()
+    }
+}
+
+/// Encoded like its elements, without the trailing bottom ones.
+#[cfg(feature = "minicbor")]
+impl<T> minicbor::Encode for VecLattice<T>
+where
+    T: Semilattice + minicbor::Encode,
+{
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.encode(self.present())?.ok()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for VecLattice<T>
+where
+    T: Semilattice + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.present().serialize(serializer)
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_decomposition, partially_verify_semilattice_laws, Max};
+
+    let a = VecLattice {
+        inner: vec![Max(1), Max(5)],
+    };
+    let b = VecLattice {
+        inner: vec![Max(3), Max(2), Max(7)],
+    };
+    let c = VecLattice {
+        inner: vec![Max(0), Max(6)],
+    };
+
+    let d = a.clone().join(b.clone()).join(c.clone());
+
+    assert_eq!(
+        d,
+        VecLattice {
+            inner: vec![Max(3), Max(6), Max(7)],
+        }
+    );
+    assert_eq!(
+        d.clone().delta(&b),
+        VecLattice {
+            inner: vec![Max(i32::MIN), Max(6)],
+        }
+    );
+
+    let mut e = a.clone();
+    assert_eq!(e.join_assign_report(b.clone()).inflated, [0, 2]);
+    assert_eq!(e.join_assign_report(c.clone()).inflated, [1]);
+    assert_eq!(e, d);
+
+    // trailing bottom elements are the same as a shorter vector.
+    let mut f = a.clone();
+    f.entry_mut(3);
+    assert_eq!(f, a);
+    assert_eq!(f.partial_cmp(&a), Some(Ordering::Equal));
+    assert_eq!(f.clone().join_assign_report(a.clone()).inflated, []);
+    f.normalize();
+    assert_eq!(f.inner, a.inner);
+    f.join_assign_ref(&b);
+    assert_eq!(f, a.clone().join(b.clone()));
+
+    // bottom elements in the tail of `other` are not reported.
+    let mut g = VecLattice::singleton(Max(1u8));
+    let tail = VecLattice {
+        inner: vec![Max(1u8), Max(0), Max(5)],
+    };
+    assert_eq!(g.join_assign_report(tail).inflated, [2]);
+
+    partially_verify_semilattice_laws([a.clone(), b.clone(), c.clone(), d.clone()]);
+    partially_verify_decomposition([
+        a,
+        b,
+        c,
+        d,
+        VecLattice {
+            inner: vec![Max(2), Max(i32::MIN)],
+        },
+    ]);
 }
@@ -0,0 +1,179 @@
+use core::{borrow::Borrow, cmp};
+
+use crate::{Decompose, JoinRef, Lattice, MapLattice, Max, Semilattice};
+
+/// A unique event: the `counter`th event of an actor.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "minicbor", derive(minicbor::Encode, minicbor::Decode))]
+pub struct Dot<A> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub actor: A,
+    #[cfg_attr(feature = "minicbor", n(1))]
+    pub counter: u64,
+}
+
+/// A version vector. Each actor's entry counts the events of that actor which
+/// are known, and the partial order is the causal order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "minicbor",
+    derive(minicbor::Encode, minicbor::Decode),
+    cbor(transparent)
+)]
+pub struct VersionVector<A> {
+    #[cfg_attr(feature = "minicbor", n(0))]
+    pub inner: MapLattice<A, Max<u64>>,
+}
+
+impl<A> VersionVector<A>
+where
+    A: Ord,
+{
+    pub fn get<Q>(&self, actor: &Q) -> u64
+    where
+        A: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.inner.entry(actor).map_or(0, |v| v.0)
+    }
+
+    /// Record the next event of `actor`, and return its dot.
+    pub fn increment(&mut self, actor: A) -> Dot<A>
+    where
+        A: Clone,
+    {
+        let counter = &mut self.inner.entry_mut(&actor).0;
+        *counter += 1;
+
+        Dot {
+            counter: *counter,
+            actor,
+        }
+    }
+
+    pub fn contains(&self, dot: &Dot<A>) -> bool {
+        dot.counter <= self.get(&dot.actor)
+    }
+
+    pub fn happened_before(&self, other: &Self) -> bool {
+        self < other
+    }
+
+    pub fn concurrent(&self, other: &Self) -> bool {
+        self.partial_cmp(other).is_none()
+    }
+}
+
+impl<A> Default for VersionVector<A> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+        }
+    }
+}
+
+impl<A> FromIterator<Dot<A>> for VersionVector<A>
+where
+    A: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = Dot<A>>>(iter: I) -> Self {
+        let mut vv = Self::default();
+        for dot in iter {
+            vv.inner.insert(dot.actor, Max(dot.counter));
+        }
+        vv
+    }
+}
+
+impl<A> PartialOrd for VersionVector<A>
+where
+    A: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.inner.partial_cmp(&other.inner)
+    }
+}
+
+impl<A> Semilattice for VersionVector<A>
+where
+    A: Ord,
+{
+    fn join(self, other: Self) -> Self {
+        Self {
+            inner: self.inner.join(other.inner),
+        }
+    }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        self.inner.join_assign_changed(other.inner)
+    }
+
+    fn delta(self, since: &Self) -> Self {
+        Self {
+            inner: self.inner.delta(&since.inner),
+        }
+    }
+}
+
+impl<A> JoinRef for VersionVector<A>
+where
+    A: Ord + Clone,
+{
+    fn join_assign_ref(&mut self, other: &Self) {
+        self.inner.join_assign_ref(&other.inner)
+    }
+}
+
+impl<A> Lattice for VersionVector<A>
+where
+    A: Ord,
+{
+    fn meet(self, other: Self) -> Self {
+        Self {
+            inner: self.inner.meet(other.inner),
+        }
+    }
+}
+
+impl<A> Decompose for VersionVector<A>
+where
+    A: Ord + Clone,
+{
+    fn decompose(self, mut func: impl FnMut(Self)) {
+        self.inner.decompose(|inner| func(Self { inner }))
+    }
+}
+
+#[test]
+fn check_laws() {
+    use crate::{partially_verify_decomposition, partially_verify_distributive_lattice_laws};
+
+    let mut a = VersionVector::default();
+    let alice = a.increment("alice");
+
+    let mut b = a.clone();
+    b.increment("bob");
+
+    let mut c = a.clone();
+    let carol = c.increment("carol");
+
+    let d = b.clone().join(c.clone());
+
+    assert_eq!(
+        alice,
+        Dot {
+            actor: "alice",
+            counter: 1
+        }
+    );
+    assert!(a.happened_before(&b) && a.happened_before(&c));
+    assert!(b.concurrent(&c));
+    assert!(!b.contains(&carol) && d.contains(&carol));
+    assert_eq!(d.get("carol"), 1);
+    assert_eq!(VersionVector::from_iter([alice, carol]), c);
+
+    partially_verify_distributive_lattice_laws([a.clone(), b.clone(), c.clone(), d.clone()]);
+    partially_verify_decomposition([a, b, c, d]);
+}
@@ -1,13 +1,191 @@
-use semilog::Semilattice;
+use semilog::{
+    partially_verify_decomposition, partially_verify_distributive_lattice_laws,
+    partially_verify_semilattice_laws, verify_finite_semilattice, BoundedLattice, Decompose,
+    Finite, Lattice, Max, Min, Redactable, Semilattice,
+};
 
-#[derive(Default, PartialEq, Semilattice)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Semilattice, Decompose, BoundedLattice, Lattice, Finite,
+)]
 struct PairR<A, B> {
     a: A,
     b: B,
 }
 
-#[derive(Default, PartialEq, Semilattice)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Semilattice, Decompose, BoundedLattice, Lattice, Finite,
+)]
 struct PairT<A, B>(A, B);
 
-#[derive(Default, PartialEq, Semilattice)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Semilattice, Decompose, BoundedLattice, Lattice, Finite,
+)]
 struct Singleton;
+
+#[derive(Clone, Debug, Default, PartialEq, Semilattice, BoundedLattice)]
+enum Status {
+    #[default]
+    Open,
+    Closed,
+    Locked,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Semilattice, BoundedLattice)]
+enum Edit {
+    #[semilattice(top)]
+    Redacted,
+    Draft(Max<u8>),
+    Published {
+        version: Max<u8>,
+        reviewed: bool,
+    },
+    #[default]
+    #[semilattice(bottom)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Semilattice, BoundedLattice)]
+enum Sum<A, B> {
+    Left(A),
+    Right(B),
+}
+
+impl<A: Default, B> Default for Sum<A, B> {
+    fn default() -> Self {
+        Self::Left(A::default())
+    }
+}
+
+fn edits() -> impl Iterator<Item = Edit> + Clone {
+    let published = (0..2).flat_map(|version| {
+        [false, true].map(|reviewed| Edit::Published {
+            version: Max(version),
+            reviewed,
+        })
+    });
+
+    [Edit::Unknown, Edit::Draft(Max(0)), Edit::Draft(Max(1))]
+        .into_iter()
+        .chain(published)
+        .chain([Edit::Redacted])
+}
+
+#[test]
+fn check_laws() {
+    partially_verify_semilattice_laws((-3..3).map(|x| PairR {
+        a: Max(x),
+        b: Min(x * x),
+    }));
+    partially_verify_semilattice_laws((-3..3).map(|x| PairT(Min(x), Max(-x))));
+    partially_verify_semilattice_laws([Singleton]);
+    partially_verify_semilattice_laws([Status::Open, Status::Closed, Status::Locked]);
+    partially_verify_semilattice_laws(edits());
+    partially_verify_semilattice_laws(
+        (-3..3).flat_map(|x| [Sum::Left(Max(x)), Sum::Right(Min(x))]),
+    );
+}
+
+#[test]
+fn check_sum() {
+    // variants are ordered by their declaration.
+    assert!(Status::Open < Status::Closed && Status::Closed < Status::Locked);
+    assert_eq!(Status::Locked.join(Status::Closed), Status::Locked);
+
+    // the bottom and top variants are below and above all others.
+    assert!(Edit::Unknown.is_bottom() && !Edit::Draft(Max(0)).is_bottom());
+    assert!(edits().all(|edit| edit.leq(&Edit::Redacted)));
+
+    // the same variant joins its fields, and a later one wins.
+    let published = |version, reviewed| Edit::Published {
+        version: Max(version),
+        reviewed,
+    };
+    assert_eq!(published(2, false).partial_cmp(&published(1, true)), None);
+    assert_eq!(
+        published(2, false).join(published(1, true)),
+        published(2, true)
+    );
+    assert_eq!(
+        Edit::Draft(Max(9)).join(published(1, false)),
+        published(1, false)
+    );
+    assert_eq!(
+        published(2, true).delta(&published(2, false)),
+        published(0, true)
+    );
+    assert_eq!(
+        Edit::Draft(Max(9)).delta(&published(1, false)),
+        Edit::Unknown
+    );
+
+    let mut edit = Edit::Draft(Max(1));
+    assert!(edit.join_assign_changed(published(0, false)));
+    assert!(!edit.join_assign_changed(Edit::Draft(Max(5))));
+    assert_eq!(edit, published(0, false));
+}
+
+#[test]
+fn check_decomposition() {
+    partially_verify_decomposition((-3..3).map(|x| PairR {
+        a: Max(x),
+        b: Min(x * x),
+    }));
+    partially_verify_decomposition((-3..3).map(|x| PairT(Min(x), Max(-x))));
+    partially_verify_decomposition([Singleton]);
+}
+
+#[test]
+fn check_top() {
+    let top = PairR::<Max<u8>, Min<u8>>::top();
+    assert_eq!(
+        top,
+        PairR {
+            a: Max(255),
+            b: Min(0)
+        }
+    );
+    assert!(top.is_top());
+    assert!(!PairR {
+        a: Max(255),
+        b: Min(1)
+    }
+    .is_top());
+
+    assert!(PairT::<Max<u8>, Min<u8>>::top().is_top());
+    assert!(Singleton.is_top());
+
+    assert_eq!(Status::top(), Status::Locked);
+    assert!(Status::Locked.is_top() && !Status::Closed.is_top());
+    assert_eq!(Edit::top(), Edit::Redacted);
+    assert!(Edit::Redacted.is_top() && !Edit::Draft(Max(255)).is_top());
+    assert_eq!(Sum::<Max<u8>, Min<u8>>::top(), Sum::Right(Min(0)));
+    assert!(!Sum::<Max<u8>, Min<u8>>::Left(Max(255)).is_top());
+}
+
+#[test]
+fn check_meet() {
+    partially_verify_distributive_lattice_laws((-3..3).map(|x| PairR {
+        a: Max(x),
+        b: Min(x * x),
+    }));
+    partially_verify_distributive_lattice_laws((-3..3).map(|x| PairT(Min(x), Max(-x))));
+    partially_verify_distributive_lattice_laws([Singleton]);
+}
+
+#[test]
+fn check_finite() {
+    assert_eq!(
+        verify_finite_semilattice::<PairR<Redactable<bool>, Redactable<()>>>(),
+        Ok(12)
+    );
+    assert_eq!(
+        verify_finite_semilattice::<PairT<Redactable<()>, Redactable<()>>>(),
+        Ok(9)
+    );
+    assert_eq!(verify_finite_semilattice::<Singleton>(), Ok(1));
+}
+
+#[test]
+fn check_errors() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
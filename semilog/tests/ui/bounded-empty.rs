@@ -0,0 +1,6 @@
+use semilog::BoundedLattice;
+
+#[derive(BoundedLattice)]
+enum Never {}
+
+fn main() {}
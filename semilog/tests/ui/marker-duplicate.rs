@@ -0,0 +1,13 @@
+use semilog::BoundedLattice;
+
+#[derive(Clone, Debug, Default, PartialEq, semilog::Semilattice, BoundedLattice)]
+enum Status {
+    #[default]
+    Open,
+    #[semilattice(top)]
+    Closed,
+    #[semilattice(top)]
+    Locked,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use semilog::{Max, Semilattice};
+
+#[derive(Clone, Debug, Default, PartialEq, Semilattice)]
+enum Status {
+    #[default]
+    Open,
+    #[semilattice(top)]
+    Closed(Max<u8>),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use semilog::Semilattice;
+
+#[derive(Clone, Debug, Default, PartialEq, Semilattice)]
+enum Status {
+    #[default]
+    Open,
+    #[semilattice(highest)]
+    Closed,
+}
+
+fn main() {}
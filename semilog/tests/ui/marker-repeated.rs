@@ -0,0 +1,12 @@
+use semilog::Semilattice;
+
+#[derive(Clone, Debug, Default, PartialEq, Semilattice)]
+enum Status {
+    #[default]
+    Open,
+    #[semilattice(top)]
+    #[semilattice(bottom)]
+    Closed,
+}
+
+fn main() {}
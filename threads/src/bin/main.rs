@@ -83,7 +83,7 @@ fn main() -> Result<(), pico_args::Error> {
             println!("{}", minicbor::display(&dump));
         }
         "list" => {
-            Detailed::default().join_root(root).display();
+            Detailed::default().join_root(&root).display();
             return Ok(());
         }
         "new" => {
@@ -1,42 +1,75 @@
-use core::ops;
+use core::{cmp, ops};
 
 use std::collections::BTreeMap;
 
-use semilog::{MapLattice, Max, Redactable, Semilattice, SetLattice, VecLattice};
+use semilog::{
+    JoinRef, MapLattice, Redactable, Semilattice, Sequence, SetLattice, Toggle, ToggleState,
+    VecLattice,
+};
 
-use crate::{ActorID, MessageID, Owned, Patchset, Reaction, Root, Shared, Slice, Tag};
+use crate::{ActorID, MessageID, Owned, Patchset, Reaction, Root, Shared, Slice, Tag, TagVote};
 
-#[derive(Default, Debug, Clone, Semilattice, PartialEq, minicbor::Encode, minicbor::Decode)]
+// The state type `S` is only a marker of the toggles, so it needs no bounds.
+#[derive(Debug, Clone, minicbor::Encode, minicbor::Decode)]
 #[cbor(transparent)]
-pub struct Vote<const N: usize>(#[n(0)] MapLattice<ActorID, Max<u64>>);
+pub struct Vote<S>(
+    #[n(0)]
+    #[cbor(bound = "S")]
+    MapLattice<ActorID, Toggle<S>>,
+);
+
+impl<S> Default for Vote<S> {
+    fn default() -> Self {
+        Self(MapLattice::default())
+    }
+}
+
+impl<S> PartialEq for Vote<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
 
-impl<const N: usize> ops::Deref for Vote<N> {
-    type Target = MapLattice<ActorID, Max<u64>>;
+impl<S> PartialOrd for Vote<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<S> Semilattice for Vote<S> {
+    fn join(self, other: Self) -> Self {
+        Self(self.0.join(other.0))
+    }
+
+    fn join_assign_changed(&mut self, other: Self) -> bool {
+        self.0.join_assign_changed(other.0)
+    }
+}
+
+impl<S> ops::Deref for Vote<S> {
+    type Target = MapLattice<ActorID, Toggle<S>>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl<const N: usize> ops::DerefMut for Vote<N> {
+impl<S> ops::DerefMut for Vote<S> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
 }
 
-impl<const N: usize> Vote<N> {
-    pub fn aggregate(&self) -> [usize; N] {
-        let mut res = [0; N];
-
-        for (_, v) in &self.inner {
-            // modulo for arbitrary N isn't efficient, but if N is always a
-            // power of two, this becomes a bit-mask. Any excess values could
-            // be reserved or may be considered equivalent to the highest
-            // element.
-            res[v.0 as usize % N] += 1;
-        }
-
-        res
+impl<S> Vote<S>
+where
+    S: ToggleState + PartialEq,
+{
+    /// The number of actors in `state`.
+    pub fn aggregate(&self, state: S) -> usize {
+        self.inner
+            .iter()
+            .filter(|(_, v)| v.state() == state)
+            .count()
     }
 }
 
@@ -49,11 +82,13 @@ struct Comment {
     #[n(2)]
     responses: SetLattice<MessageID>,
     #[n(3)]
-    tags: MapLattice<Tag, Vote<4>>,
+    tags: MapLattice<Tag, Vote<TagVote>>,
     #[n(4)]
-    reactions: MapLattice<Reaction, Vote<2>>,
+    reactions: MapLattice<Reaction, Vote<bool>>,
     #[n(5)]
     commits: VecLattice<SetLattice<Patchset>>,
+    #[n(6)]
+    text: Option<Sequence<ActorID, char>>,
 }
 
 #[derive(Default, Debug, Clone, Semilattice, PartialEq, minicbor::Encode, minicbor::Decode)]
@@ -65,36 +100,47 @@ pub struct Detailed {
 }
 
 impl Detailed {
-    pub fn join_root(mut self, other: Root) -> Self {
-        for (actor, Slice { owned, shared }) in other.inner.inner {
+    pub fn join_root(mut self, other: &Root) -> Self {
+        self.join_root_changed(other);
+        self
+    }
+
+    /// Join a borrowed root into `self`, returning the comments which were
+    /// changed. Only the parts of a comment which are new are cloned.
+    pub fn join_root_changed(&mut self, other: &Root) -> SetLattice<MessageID> {
+        let mut changed = SetLattice::default();
+
+        for (actor, Slice { owned, shared }) in &other.inner.inner {
             for (
                 id,
                 Owned {
                     titles,
                     content,
                     commits,
+                    text,
                 },
-            ) in owned.inner.into_iter().enumerate()
+            ) in owned.inner.iter().enumerate()
             {
                 let id = id as u64;
                 if !titles.is_empty() {
                     self.threads.insert((actor.clone(), id));
                 }
 
-                self.comments
-                    .entry_mut(&actor)
-                    .entry_mut(id)
-                    .join_assign(Comment {
-                        titles,
-                        content,
-                        reactions: MapLattice::default(),
-                        responses: SetLattice::default(),
-                        tags: MapLattice::default(),
-                        commits,
-                    });
+                let comment = self.comments.entry_mut(actor).entry_mut(id);
+                if !(titles.leq(&comment.titles)
+                    && content.leq(&comment.content)
+                    && commits.leq(&comment.commits)
+                    && text.leq(&comment.text))
+                {
+                    comment.titles.join_assign_ref(titles);
+                    comment.content.join_assign_ref(content);
+                    comment.commits.join_assign_ref(commits);
+                    comment.text.join_assign_ref(text);
+                    changed.insert((actor.clone(), id));
+                }
             }
 
-            for (aid, comments) in shared.inner {
+            for (aid, comments) in &shared.inner {
                 for (
                     id,
                     Shared {
@@ -102,12 +148,13 @@ impl Detailed {
                         reactions,
                         responses,
                     },
-                ) in comments.inner
+                ) in &comments.inner
                 {
-                    self.comments
-                        .entry_mut(&aid)
-                        .entry_mut(id)
-                        .join_assign(Comment {
+                    if self
+                        .comments
+                        .entry_mut(aid)
+                        .entry_mut(*id)
+                        .join_assign_changed(Comment {
                             reactions: MapLattice::from_iter(reactions.iter().map(|(r, v)| {
                                 (r.clone(), Vote(MapLattice::singleton(actor.clone(), *v)))
                             })),
@@ -115,15 +162,24 @@ impl Detailed {
                                 (r.clone(), Vote(MapLattice::singleton(actor.clone(), *v)))
                             })),
                             responses: SetLattice::from_iter(
-                                responses.iter().map(|id| (actor.clone(), id.0)),
+                                responses.iter().map(|id| (actor.clone(), *id)),
                             ),
                             ..Default::default()
-                        });
+                        })
+                    {
+                        changed.insert((aid.clone(), *id));
+                    }
                 }
             }
         }
 
-        self
+        changed
+    }
+
+    /// The collaboratively edited text of a comment, if it has any.
+    pub fn text(&self, id: &MessageID) -> Option<String> {
+        let text = self.comments.entry(&id.0)?.entry(id.1)?.text.as_ref()?;
+        (!text.is_empty()).then(|| text.to_string())
     }
 }
 
@@ -132,7 +188,7 @@ impl Detailed {
     pub fn display(&self) {
         let mut stack = Vec::new();
 
-        for (mid, _) in &**self.threads {
+        for mid in &self.threads {
             stack.clear();
             stack.push((0, mid));
 
@@ -151,8 +207,8 @@ impl Detailed {
 
                 let mut tag_votes = BTreeMap::new();
                 for (tag, votes) in &*comment.tags {
-                    let va = votes.aggregate();
-                    *tag_votes.entry(tag).or_insert(0) += va[1] as i64 - va[2] as i64;
+                    *tag_votes.entry(tag).or_insert(0) += votes.aggregate(TagVote::Positive) as i64
+                        - votes.aggregate(TagVote::Negative) as i64;
                 }
 
                 print!("Tags: ");
@@ -164,6 +220,9 @@ impl Detailed {
                 for (version, content) in comment.content.iter().enumerate() {
                     println!("Body [{}]: {:?}", version, content);
                 }
+                if let Some(text) = comment.text.as_ref().filter(|x| !x.is_empty()) {
+                    println!("Text: {:?}", text.to_string());
+                }
                 print!("Reactions: ");
                 for (reaction, votes) in &*comment.reactions {
                     print!("{} ({:?})", reaction, votes);
@@ -1,4 +1,9 @@
-use semilog::{MapLattice, Max, Redactable, Semilattice, SetLattice, VecLattice};
+use core::ops;
+
+use semilog::{
+    CausalLength, JoinRef, MapLattice, Redactable, Semilattice, Sequence, SetLattice, Toggle,
+    ToggleState, VecLattice,
+};
 
 pub mod detailed;
 
@@ -14,6 +19,37 @@ pub type Tag = String;
 
 pub type Oid = Vec<u8>;
 
+/// An actor's vote on a tag, as the state of a `Toggle`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TagVote {
+    Neutral,
+    Positive,
+    Negative,
+    Invalid,
+}
+
+impl ToggleState for TagVote {
+    const N: usize = 4;
+
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => TagVote::Neutral,
+            1 => TagVote::Positive,
+            2 => TagVote::Negative,
+            _ => TagVote::Invalid,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            TagVote::Neutral => 0,
+            TagVote::Positive => 1,
+            TagVote::Negative => 2,
+            TagVote::Invalid => 3,
+        }
+    }
+}
+
 #[derive(
     Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, minicbor::Encode, minicbor::Decode,
 )]
@@ -26,7 +62,9 @@ struct Patchset {
     end: Oid,
 }
 
-#[derive(Clone, Default, Debug, PartialEq, Semilattice, minicbor::Encode, minicbor::Decode)]
+#[derive(
+    Clone, Default, Debug, PartialEq, Semilattice, JoinRef, minicbor::Encode, minicbor::Decode,
+)]
 pub struct Owned {
     #[n(0)]
     titles: VecLattice<SetLattice<String>>,
@@ -34,19 +72,27 @@ pub struct Owned {
     content: VecLattice<Redactable<String>>,
     #[n(2)]
     commits: VecLattice<SetLattice<Patchset>>,
+    /// Content which is edited collaboratively, rather than as whole versions.
+    /// It is absent unless the message has any.
+    #[n(3)]
+    text: Option<Sequence<ActorID, char>>,
 }
 
-#[derive(Clone, Default, Debug, PartialEq, Semilattice, minicbor::Encode, minicbor::Decode)]
+#[derive(
+    Clone, Default, Debug, PartialEq, Semilattice, JoinRef, minicbor::Encode, minicbor::Decode,
+)]
 pub struct Shared {
     #[n(0)]
     responses: SetLattice<u64>,
     #[n(1)]
-    tags: MapLattice<Tag, Max<u64>>,
+    tags: MapLattice<Tag, Toggle<TagVote>>,
     #[n(2)]
-    reactions: MapLattice<Tag, Max<u64>>,
+    reactions: MapLattice<Tag, CausalLength>,
 }
 
-#[derive(Clone, Default, Debug, PartialEq, Semilattice, minicbor::Encode, minicbor::Decode)]
+#[derive(
+    Clone, Default, Debug, PartialEq, Semilattice, JoinRef, minicbor::Encode, minicbor::Decode,
+)]
 pub struct Slice {
     #[n(0)]
     owned: VecLattice<Owned>,
@@ -54,7 +100,9 @@ pub struct Slice {
     shared: MapLattice<ActorID, MapLattice<u64, Shared>>,
 }
 
-#[derive(Clone, Default, Debug, PartialEq, Semilattice, minicbor::Encode, minicbor::Decode)]
+#[derive(
+    Clone, Default, Debug, PartialEq, Semilattice, JoinRef, minicbor::Encode, minicbor::Decode,
+)]
 pub struct Root {
     #[n(0)]
     pub inner: MapLattice<ActorID, Slice>,
@@ -67,7 +115,7 @@ pub struct Actor<'a> {
 }
 
 impl Actor<'_> {
-    pub fn new(slice: &mut Slice, id: ActorID) -> Actor {
+    pub fn new(slice: &mut Slice, id: ActorID) -> Actor<'_> {
         Actor { id, slice }
     }
 
@@ -81,8 +129,9 @@ impl Actor<'_> {
 
         self.slice.owned.push(Owned {
             titles: VecLattice::singleton(SetLattice::singleton(title)),
-            content: VecLattice::singleton(Redactable::Data(message)),
+            content: VecLattice::singleton(Redactable::new(message)),
             commits: VecLattice::default(),
+            text: None,
         });
 
         self.slice
@@ -92,7 +141,7 @@ impl Actor<'_> {
             .tags
             .join_assign(
                 tags.into_iter()
-                    .map(|x| (x, Max(1)))
+                    .map(|x| (x, Toggle::from(TagVote::Positive)))
                     .collect::<Vec<_>>()
                     .into(),
             );
@@ -105,8 +154,9 @@ impl Actor<'_> {
 
         self.slice.owned.push(Owned {
             titles: Default::default(),
-            content: VecLattice::singleton(Redactable::Data(message)),
+            content: VecLattice::singleton(Redactable::new(message)),
             commits: Default::default(),
+            text: None,
         });
 
         self.slice
@@ -123,32 +173,44 @@ impl Actor<'_> {
         let content = &mut self.slice.owned.entry_mut(id).content;
         let version = content.len() as u64;
 
-        content.push(Redactable::Data(message));
+        content.push(Redactable::new(message));
 
         version
     }
 
+    /// Insert into the collaboratively edited text of a message.
+    pub fn insert_text(&mut self, id: u64, pos: usize, text: &str) {
+        self.slice
+            .owned
+            .entry_mut(id)
+            .text
+            .get_or_insert_with(Sequence::default)
+            .insert(self.id.clone(), pos, text.chars());
+    }
+
+    pub fn delete_text(&mut self, id: u64, range: ops::Range<usize>) {
+        if let Some(text) = &mut self.slice.owned.entry_mut(id).text {
+            text.delete(range);
+        }
+    }
+
     pub fn redact(&mut self, id: u64, version: u64) {
         self.slice
             .owned
             .entry_mut(id)
             .content
             .entry_mut(version)
-            .join_assign(Redactable::Redacted);
+            .redact();
     }
 
     pub fn react(&mut self, id: MessageID, reaction: Reaction, vote: bool) {
-        let stored_vote = self
-            .slice
+        self.slice
             .shared
             .entry_mut(&id.0)
             .entry_mut(&id.1)
             .reactions
-            .entry_mut(&reaction);
-
-        if stored_vote.0 % 2 != vote as u64 {
-            stored_vote.0 += 1;
-        }
+            .entry_mut(&reaction)
+            .set_present(vote);
     }
 
     pub fn adjust_tags(
@@ -160,24 +222,11 @@ impl Actor<'_> {
         let tags = &mut self.slice.shared.entry_mut(&id.0).entry_mut(&id.1).tags;
 
         for tag in add {
-            let vote = tags.entry_mut(&tag);
-            // 0 = neutral, 1 = positive, 2 = negative, 3 = invalid
-            match vote.0 % 4 {
-                0 => vote.0 += 1,
-                1 => (),
-                2 => vote.0 += 3,
-                _ => vote.0 += 2,
-            }
+            tags.entry_mut(&tag).set(TagVote::Positive);
         }
 
         for tag in remove {
-            let vote = tags.entry_mut(&tag);
-            match vote.0 % 4 {
-                0 => vote.0 += 2,
-                1 => vote.0 += 1,
-                2 => (),
-                _ => vote.0 += 3,
-            }
+            tags.entry_mut(&tag).set(TagVote::Negative);
         }
     }
 }
@@ -198,7 +247,7 @@ impl Root {
             .expect("Failed to create tree.");
 
         tree.insert(
-            &actor_name,
+            actor_name,
             repo.blob(&buffer).expect("Failed to record blob."),
             0o160000,
         )
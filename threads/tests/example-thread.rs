@@ -1,6 +1,6 @@
 use core::mem;
-use semilog::Semilattice;
-use threads::{Actor, Root};
+use semilog::{JoinRef, Semilattice};
+use threads::{detailed::Detailed, Actor, Root};
 
 // https://gchq.github.io/CyberChef/#recipe=From_Decimal('Comma',false)To_Hex('0x%20with%20comma',0)
 // (assert_eq failure -> decimal list -> hex list)
@@ -28,7 +28,7 @@ fn example_discussion_test_vector() {
     bob.adjust_tags(a0, ["regression".to_owned()], ["incorrect-tag".to_owned()]);
 
     // Alice reacts form her phone
-    let _a1 = alice_1.react(b0.clone(), ":hourglass:".to_owned(), true);
+    alice_1.react(b0.clone(), ":hourglass:".to_owned(), true);
 
     // responds from her laptop
     let a2 = alice_0.reply(b0, "Ah! Test #3 failed. [..]".to_owned());
@@ -142,4 +142,106 @@ fn example_discussion_test_vector() {
             0x65, 0x67, 0x72, 0x65, 0x73, 0x73, 0x69, 0x6f, 0x6e, 0x81, 0x01, 0x80
         ]
     );
+
+    // Only the first merge into a materialized view changes any comments.
+    let mut detailed = Detailed::default();
+    let changed = detailed.join_root_changed(&root);
+    assert_eq!(
+        changed.iter().collect::<Vec<_>>(),
+        [
+            &("alice".to_owned(), 0),
+            &("alice".to_owned(), 1),
+            &("alice#0".to_owned(), 0),
+            &("bob".to_owned(), 0),
+        ]
+    );
+    assert!(detailed.join_root_changed(&root).is_empty());
+}
+
+#[test]
+fn concurrent_text_edits() {
+    let mut laptop_slice = Default::default();
+    let mut laptop = Actor::new(&mut laptop_slice, "alice#0".to_owned());
+    let mut phone_slice = Default::default();
+    let mut phone = Actor::new(&mut phone_slice, "alice#1".to_owned());
+
+    let (_, id) = laptop.reply(("bob".to_owned(), 0), "Ah! Test #3 failed. [..]".to_owned());
+    laptop.insert_text(id, 0, "Test #3 failed.");
+    phone.slice.join_assign_ref(laptop.slice);
+
+    // both devices edit the text before syncing.
+    laptop.delete_text(id, 6..7);
+    laptop.insert_text(id, 6, "4");
+    phone.insert_text(id, 0, "Ah! ");
+
+    let mut root = Root::default();
+    root.inner
+        .entry_mut("alice")
+        .join_assign(mem::take(laptop.slice));
+    root.inner
+        .entry_mut("alice")
+        .join_assign(mem::take(phone.slice));
+
+    let mut buffer = Vec::new();
+    minicbor::encode(&root, &mut buffer).expect("Failed to CBOR encode root.");
+    let root: Root = minicbor::decode(&buffer).expect("Failed to CBOR decode root.");
+
+    let detailed = Detailed::default().join_root(&root);
+    assert_eq!(
+        detailed.text(&("alice".to_owned(), id)),
+        Some("Ah! Test #4 failed.".to_owned())
+    );
+}
+
+#[test]
+fn untouched_messages_are_not_stored() {
+    let mut slice = Default::default();
+    let mut alice = Actor::new(&mut slice, "alice".to_owned());
+    alice.new_thread(
+        "Issue with feature X".to_owned(),
+        "Hello world.".to_owned(),
+        ["bug".to_owned()],
+    );
+
+    let before = alice.slice.clone();
+    let mut expected = Vec::new();
+    minicbor::encode(&before, &mut expected).expect("Failed to CBOR encode slice.");
+
+    // these leave bottom entries behind for messages which do not exist.
+    alice.delete_text(3, 0..1);
+    alice.react(("bob".to_owned(), 2), ":+1:".to_owned(), false);
+    assert_eq!(*alice.slice, before);
+
+    let mut buffer = Vec::new();
+    minicbor::encode(&alice.slice, &mut buffer).expect("Failed to CBOR encode slice.");
+    assert_eq!(buffer, expected);
+}
+
+#[test]
+fn merge_remote_root_into_cache() {
+    let mut alice_slice = Default::default();
+    let mut alice = Actor::new(&mut alice_slice, "alice".to_owned());
+    let a0 = alice.new_thread(
+        "Issue with feature X".to_owned(),
+        "Hello world.".to_owned(),
+        ["bug".to_owned()],
+    );
+
+    let mut cache = Root::default();
+    cache.inner.insert("alice".to_owned(), alice.slice.clone());
+
+    // the remote has seen a reply from Bob, and a later edit from Alice.
+    let mut bob_slice = Default::default();
+    let mut bob = Actor::new(&mut bob_slice, "bob".to_owned());
+    bob.reply(a0.clone(), "Huh. Can you run the tests?".to_owned());
+    alice.edit(a0.1, "Hello world, again.".to_owned());
+
+    let mut remote = Root::default();
+    remote.inner.insert("alice".to_owned(), alice.slice.clone());
+    remote.inner.insert("bob".to_owned(), bob.slice.clone());
+
+    let expected = cache.clone().join(remote.clone());
+    cache.join_assign_ref(&remote);
+    assert_eq!(cache, expected);
+    assert_eq!(cache, remote);
 }